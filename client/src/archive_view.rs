@@ -0,0 +1,89 @@
+use std::io::{Stdout, Write};
+
+use colored::Colorize;
+use config::Config;
+use crossterm::{
+    cursor,
+    event::{read, Event, KeyCode},
+    execute, terminal,
+};
+use reminder::{api_error::ApiError, archive::ArchivedReminder};
+
+use crate::key_reader::{fetch, send};
+
+/// Shows the archive until 'esc' or 'q' is pressed.
+/// 'r' or 'ENTER' restores the reminder under the cursor, 'd' deletes it for good.
+pub fn browse_archive(
+    stdout: &mut Stdout,
+    request_client: &reqwest::blocking::Client,
+    config: &Config,
+) -> Result<(), ApiError> {
+    let archive_url = format!(
+        "http://{}:{}/archive",
+        config.network().remote_ip(),
+        config.network().port()
+    );
+    let mut archived: Vec<ArchivedReminder> = fetch(request_client.get(&archive_url))?;
+    let mut cursor_position: usize = 0;
+    loop {
+        cursor_position = cursor_position.min(archived.len().saturating_sub(1));
+        let _trash_bin = execute!(
+            stdout,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        );
+        if stdout
+            .write_all(build_archive_list(&archived, cursor_position).as_bytes())
+            .is_err()
+        {
+            return Ok(());
+        }
+        let _trash_bin = stdout.flush();
+        let Ok(Event::Key(event)) = read() else {
+            continue;
+        };
+        #[allow(clippy::wildcard_enum_match_arm)]
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                cursor_position = cursor_position.saturating_add(1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                cursor_position = cursor_position.saturating_sub(1);
+            }
+            KeyCode::Char('r') | KeyCode::Enter if !archived.is_empty() => {
+                send(request_client.put(format!("{archive_url}/{cursor_position}/restore")))?;
+                archived = fetch(request_client.get(&archive_url))?;
+            }
+            KeyCode::Char('d') if !archived.is_empty() => {
+                send(request_client.delete(format!("{archive_url}/{cursor_position}")))?;
+                archived = fetch(request_client.get(&archive_url))?;
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'A') => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+fn build_archive_list(archived: &[ArchivedReminder], cursor_position: usize) -> String {
+    let mut result = String::new();
+    result.push_str(
+        format!(
+            "{}\n\r",
+            "Archive: 'j', 'k' -> up, down | 'r' -> restore | 'd' -> delete | 'esc' -> back"
+                .bright_yellow()
+        )
+        .as_str(),
+    );
+    if archived.is_empty() {
+        result.push_str(" The archive is empty\n\r");
+    }
+    for (i, archived_reminder) in archived.iter().enumerate() {
+        if i == cursor_position {
+            result.push_str(format!("[{archived_reminder}]\n\r").as_str());
+        } else {
+            result.push_str(format!(" {archived_reminder}\n\r").as_str());
+        }
+    }
+    result
+}
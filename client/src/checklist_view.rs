@@ -0,0 +1,117 @@
+use std::io::{stdin, Stdout, Write};
+
+use colored::Colorize;
+use config::Config;
+use crossterm::{
+    cursor,
+    event::{read, Event, KeyCode},
+    execute, terminal,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use reminder::{api_error::ApiError, reminder::Reminder};
+
+use crate::key_reader::{fetch, send};
+
+/// Shows the checklist of the reminder with `id` until 'esc' or 'q' is pressed.
+/// ' ' or 'ENTER' ticks the item under the cursor, 'a' adds a new one and 'd' removes it.
+pub fn edit_checklist(
+    stdout: &mut Stdout,
+    id: usize,
+    request_client: &reqwest::blocking::Client,
+    config: &Config,
+) -> Result<(), ApiError> {
+    let checklist_url = format!(
+        "http://{}:{}/reminders/{id}/checklist",
+        config.network().remote_ip(),
+        config.network().port()
+    );
+    let mut cursor_position: usize = 0;
+    loop {
+        let reminders: Vec<Reminder> = fetch(request_client.get(format!(
+            "http://{}:{}/reminders",
+            config.network().remote_ip(),
+            config.network().port()
+        )))?;
+        let Some(reminder) = reminders.into_iter().find(|reminder| reminder.id() == id) else {
+            return Err(ApiError::reminder_not_found(id));
+        };
+        let item_amount = reminder.checklist().len();
+        cursor_position = cursor_position.min(item_amount.saturating_sub(1));
+        let _trash_bin = execute!(
+            stdout,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        );
+        if stdout
+            .write_all(build_checklist(&reminder, cursor_position).as_bytes())
+            .is_err()
+        {
+            return Ok(());
+        }
+        let _trash_bin = stdout.flush();
+        let Ok(Event::Key(event)) = read() else {
+            continue;
+        };
+        #[allow(clippy::wildcard_enum_match_arm)]
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                cursor_position = cursor_position.saturating_add(1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                cursor_position = cursor_position.saturating_sub(1);
+            }
+            KeyCode::Char(' ') | KeyCode::Enter if item_amount > 0 => {
+                send(request_client.put(format!("{checklist_url}/{cursor_position}/toggle_done")))?;
+            }
+            KeyCode::Char('a') => {
+                let _trash_bin = stdout.write_all(b"New item: ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut text = String::new();
+                let _trash_bin = stdin().read_line(&mut text);
+                let _trash_bin = enable_raw_mode().is_ok();
+                if !text.trim().is_empty() {
+                    send(request_client.post(&checklist_url).json(&text.trim()))?;
+                    cursor_position = item_amount;
+                }
+            }
+            KeyCode::Char('d') if item_amount > 0 => {
+                send(request_client.delete(format!("{checklist_url}/{cursor_position}")))?;
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'x') => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+fn build_checklist(reminder: &Reminder, cursor_position: usize) -> String {
+    let mut result = String::new();
+    result.push_str(
+        format!(
+            "{}\n\r",
+            format!(
+                "Checklist of {}: 'j', 'k' -> up, down | ' ' -> tick | 'a' -> add | 'd' -> remove | 'esc' -> back",
+                reminder.name().trim_end()
+            )
+            .bright_yellow()
+        )
+        .as_str(),
+    );
+    if reminder.checklist().is_empty() {
+        result.push_str(" No items yet, 'a' adds one\n\r");
+    }
+    for (i, item) in reminder.checklist().iter().enumerate() {
+        let item = if item.done() {
+            item.to_string().bright_black()
+        } else {
+            item.to_string().normal()
+        };
+        if i == cursor_position {
+            result.push_str(format!("[{item}]\n\r").as_str());
+        } else {
+            result.push_str(format!(" {item}\n\r").as_str());
+        }
+    }
+    result
+}
@@ -6,32 +6,66 @@ use std::{
 use config::Config;
 use crossterm::{
     cursor,
-    event::{poll, read, Event, KeyCode, KeyModifiers},
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use duration_string::DurationString;
-use time::{format_description, Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+use reqwest::blocking::{RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use time::OffsetDateTime;
 
-use reminder::reminder::{my_local_offset, ApiReminder, Reminder, ReminderType, TimeObject};
+use reminder::{
+    api_error::{ApiError, ApiErrorCode},
+    priority::{Priority, SortOrder},
+    recurrence::Recurrence,
+    reminder::{my_local_offset, ApiReminder, BatchRequest, Reminder, ReminderType},
+    tag::{normalize_tag, parse_tags, split_tags},
+    time_input::{parse_duration, parse_time_input, TIME_INPUT_EXAMPLES},
+};
+
+use crate::{
+    archive_view::browse_archive, checklist_view::edit_checklist, selection::Selection,
+    stats_view::show_stats,
+};
+
+/// Every kind of input the time prompts understand, for error messages.
+const TIME_INPUT_KINDS: &str = "a time (9pm), date (friday 17:00) or duration (1h10m)";
 
 /// This Reads any input detected on the terminal window.
 /// This will block when a known key combination is found and there are follow up decisions to make
 /// for the user.
 /// Otherwise, this blocks for one second and returns.
-#[allow(clippy::too_many_lines)]
+/// Failed requests and unreadable inputs are returned as `ApiError`, to be shown in the status box.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 pub fn read_input(
     stdout: &mut Stdout,
     selected_reminder: &Reminder,
     all_reminders: &[Reminder],
     reminder_amount: usize,
     cursor_position: &mut usize,
+    event_scroll: &mut usize,
     request_client: &reqwest::blocking::Client,
     config: &Config,
-) -> bool {
+    selection: &mut Selection,
+    tag_filter: &mut Option<String>,
+    sort_order: &mut SortOrder,
+) -> Result<bool, ApiError> {
     if poll(std::time::Duration::from_secs(1)).unwrap_or(true) {
         #[allow(clippy::single_match, clippy::wildcard_enum_match_arm)]
         if let Ok(Event::Key(event)) = read() {
+            let selected_ids = selection.ids(all_reminders, *cursor_position);
+            if !selected_ids.is_empty() {
+                if let Some(result) = read_batch_input(
+                    stdout,
+                    &event,
+                    selected_ids,
+                    selection,
+                    request_client,
+                    config,
+                ) {
+                    return result;
+                }
+            }
             return match event.code {
                 KeyCode::Char('c') => {
                     if event.modifiers.contains(KeyModifiers::CONTROL) {
@@ -39,7 +73,13 @@ pub fn read_input(
                         let _trash_bin = disable_raw_mode().is_ok();
                         std::process::exit(0);
                     } else {
-                        false
+                        send(request_client.put(format!(
+                            "http://{}:{}/reminders/{}/confirm",
+                            config.network().remote_ip(),
+                            config.network().port(),
+                            selected_reminder.id()
+                        )))?;
+                        Ok(true)
                     }
                 }
                 KeyCode::Char('n') => {
@@ -50,123 +90,86 @@ pub fn read_input(
                     let _trash_bin = stdout.flush();
                     let _trash_bin = disable_raw_mode().is_ok();
                     if stdin().read_line(&mut name).is_err() {
-                        return false;
+                        return Ok(false);
                     };
-                    name = name.replace('\n', "");
+                    let (name, mut tags) = split_tags(name.replace('\n', "").as_str());
+                    if tags.is_empty() {
+                        tags.extend(tag_filter.clone());
+                    }
                     let mut time_input = String::new();
                     let _trash_bin = enable_raw_mode().is_ok();
                     let _trash_bin = stdout.write_all(
-                        b"End time or date (1h10m | 15:23 | 8.11.2023 | 8.11.2023 15:23): ",
+                        format!("End time or date ({TIME_INPUT_EXAMPLES} | empty for a note): ")
+                            .as_bytes(),
                     );
                     let _trash_bin = stdout.flush();
                     let _trash_bin = disable_raw_mode().is_ok();
                     if stdin().read_line(&mut time_input).is_err() {
-                        return false;
+                        return Ok(false);
                     };
                     time_input = time_input.replace('\n', "");
-                    let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
-                    let mut finish_time = OffsetDateTime::now_utc().to_offset(my_local_offset());
-                    let reminder_type: ReminderType;
-                    #[allow(unused_assignments)]
-                    let mut _duration: Duration = Duration::new(0, 0);
-                    #[allow(clippy::useless_conversion, clippy::arithmetic_side_effects)]
-                    if time_input.chars().all(|e| e.is_ascii_digit() || e == ':') {
-                        let Ok(format) = format_description::parse("[hour]:[minute]") else {
-                            return false;
-                        };
-                        let Ok(new_finish_time) = Time::parse(time_input.as_str(), &format) else {
-                            return false;
-                        };
-                        finish_time = finish_time.replace_time(new_finish_time);
-                        reminder_type = ReminderType::Time;
-                        _duration = finish_time - now;
-                    } else if time_input.chars().all(|e| e.is_ascii_digit() || e == '.') {
-                        let Ok(format) = format_description::parse("[day].[month].[year]") else {
-                            return false;
-                        };
-                        let Ok(new_finish_date) = Date::parse(time_input.as_str(), &format) else {
-                            return false;
-                        };
-                        finish_time = finish_time.replace_date(new_finish_date);
-                        reminder_type = ReminderType::Date;
-                    } else if time_input
-                        .chars()
-                        .all(|e| e.is_ascii_digit() || e == '.' || e == ':' || e == ' ')
-                    {
-                        let Ok(format) =
-                            format_description::parse("[day].[month].[year] [hour]:[minute]")
-                        else {
-                            return false;
-                        };
-                        let Ok(new_finish_date_time) =
-                            PrimitiveDateTime::parse(time_input.as_str(), &format)
-                        else {
-                            return false;
-                        };
-                        finish_time = finish_time.replace_date(new_finish_date_time.date());
-                        finish_time = finish_time.replace_time(new_finish_date_time.time());
-                        reminder_type = ReminderType::Date;
+                    let (finish_time, reminder_type) = if time_input.trim().is_empty() {
+                        (
+                            OffsetDateTime::now_utc().to_offset(my_local_offset()),
+                            ReminderType::Note,
+                        )
                     } else {
-                        let Ok(parsed_duration_string) = DurationString::from_string(time_input)
-                        else {
-                            return false;
-                        };
-                        let parsed_duration: core::time::Duration = parsed_duration_string.into();
-                        let Ok(parsed_duration) = parsed_duration.try_into() else {
-                            return false;
+                        let Ok(time_object) = parse_time_input(
+                            time_input.as_str(),
+                            OffsetDateTime::now_utc().to_offset(my_local_offset()),
+                        ) else {
+                            return Err(invalid_input_error(&time_input, TIME_INPUT_KINDS));
                         };
-                        _duration = Duration::from(parsed_duration);
-                        finish_time = now + parsed_duration;
-                        reminder_type = ReminderType::Duration;
-                    }
-                    if request_client
-                        .post(format!(
-                            "http://{}:{}/reminders",
-                            config.network().remote_ip(),
-                            config.network().port()
-                        ))
-                        .json(&ApiReminder {
-                            name,
-                            description: String::new(),
-                            finish_time,
-                            reminder_type,
-                        })
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    false
+                        (time_object.finish_time, time_object.reminder_type)
+                    };
+                    send(
+                        request_client
+                            .post(format!(
+                                "http://{}:{}/reminders",
+                                config.network().remote_ip(),
+                                config.network().port()
+                            ))
+                            .json(&ApiReminder {
+                                name,
+                                description: String::new(),
+                                finish_time,
+                                reminder_type,
+                                tags,
+                                priority: Priority::Normal,
+                                checklist: vec![],
+                            }),
+                    )?;
+                    Ok(true)
                 }
                 KeyCode::Char(' ') => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/{}/pause",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/{}/pause",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
                 }
 
                 KeyCode::Char('r') => {
-                    read_re_mode_input(stdout, selected_reminder, request_client, config);
-                    return true;
+                    read_re_mode_input(
+                        stdout,
+                        selected_reminder,
+                        all_reminders,
+                        request_client,
+                        config,
+                    )?;
+                    return Ok(true);
                 }
                 KeyCode::Char('k') => {
                     *cursor_position = cursor_position.saturating_sub(1);
-                    return false;
+                    return Ok(false);
                 }
                 KeyCode::Char('j') => {
                     if *cursor_position != reminder_amount.saturating_sub(1) {
                         *cursor_position = cursor_position.saturating_add(1);
                     }
-                    return false;
+                    return Ok(false);
                 }
                 KeyCode::Char('g') => {
                     if let Ok(Event::Key(event)) = read() {
@@ -174,11 +177,19 @@ pub fn read_input(
                             *cursor_position = 0
                         }
                     }
-                    return false;
+                    return Ok(false);
+                }
+                KeyCode::Char('K') => {
+                    *event_scroll = event_scroll.saturating_add(1);
+                    return Ok(false);
+                }
+                KeyCode::Char('J') => {
+                    *event_scroll = event_scroll.saturating_sub(1);
+                    return Ok(false);
                 }
                 KeyCode::Char('G') => {
                     *cursor_position = reminder_amount.saturating_sub(1);
-                    return false;
+                    return Ok(false);
                 }
                 KeyCode::Char('d') => {
                     if *cursor_position == reminder_amount.saturating_sub(1)
@@ -186,92 +197,100 @@ pub fn read_input(
                     {
                         *cursor_position = cursor_position.saturating_sub(1);
                     }
-                    if request_client
-                        .delete(format!(
-                            "http://{}:{}/reminders/{}",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.delete(format!(
+                        "http://{}:{}/reminders/{}",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
                 }
                 KeyCode::Char('s') => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/{}/snooze",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/{}/snooze",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
+                }
+                KeyCode::Char('S') => {
+                    let duration = read_duration(stdout, "Snooze for (10m | 1h | 2 hours): ")?;
+                    send(
+                        request_client
+                            .put(format!(
+                                "http://{}:{}/reminders/{}/snooze",
+                                config.network().remote_ip(),
+                                config.network().port(),
+                                selected_reminder.id()
+                            ))
+                            .json(&duration),
+                    )?;
+                    Ok(true)
                 }
                 KeyCode::Char('e') => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/{}/toggle_repeat",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
-                }
-                // KeyCode::Char('s') => {
-                //     if request_client
-                //         .put(format!(
-                //             "http://{}:{}/reminders/{}/toggle_send_e_message",
-                //             config.network().remote_ip(),
-                //             config.network().port(),
-                //             selected_reminder.id()
-                //         ))
-                //         .send()
-                //         .is_ok()
-                //     {
-                //         return true;
-                //     }
-                //     return false;
-                // }
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/{}/toggle_repeat",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
+                }
+                KeyCode::Char('a') => {
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/{}/toggle_nag",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
+                }
+                KeyCode::Char('m') => {
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/{}/toggle_send_e_message",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
+                }
+                KeyCode::Char('L') => {
+                    send(request_client.post(format!(
+                        "http://{}:{}/reminders/{}/save_as_template",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        selected_reminder.id()
+                    )))?;
+                    Ok(true)
+                }
+                KeyCode::Char(digit @ '1'..='9') => {
+                    let Some(index) = digit.to_digit(10) else {
+                        return Ok(false);
+                    };
+                    send(request_client.post(format!(
+                        "http://{}:{}/library/{}/instantiate",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                        index.saturating_sub(1)
+                    )))?;
+                    Ok(true)
+                }
                 KeyCode::Char('u') => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/undo",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/undo",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                    )))?;
+                    Ok(true)
                 }
                 KeyCode::Char('U') => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/redo",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/redo",
+                        config.network().remote_ip(),
+                        config.network().port(),
+                    )))?;
+                    Ok(true)
                 }
                 KeyCode::Char('/') => {
                     execute!(stdout, cursor::Show,).unwrap();
@@ -281,7 +300,7 @@ pub fn read_input(
                     let _trash_bin = stdout.flush();
                     let _trash_bin = disable_raw_mode().is_ok();
                     if stdin().read_line(&mut search_term).is_err() {
-                        return false;
+                        return Ok(false);
                     };
                     search_term = search_term.replace('\n', "").to_lowercase();
                     if let Some(found_reminder_index) = all_reminders.iter().position(|reminder| {
@@ -295,9 +314,9 @@ pub fn read_input(
                                 .contains(search_term.as_str())
                     }) {
                         *cursor_position = found_reminder_index;
-                        return false;
+                        return Ok(false);
                     }
-                    return false;
+                    return Ok(false);
                 }
                 KeyCode::Enter => {
                     let Ok(process) = Command::new("vipe")
@@ -305,7 +324,7 @@ pub fn read_input(
                         .stdout(Stdio::piped())
                         .spawn()
                     else {
-                        return false;
+                        return Ok(false);
                     };
                     #[allow(clippy::expect_used)]
                     let _trash_bin = process
@@ -318,76 +337,161 @@ pub fn read_input(
                         .stdout
                         .expect("Cant read pipe from editor")
                         .read_to_string(&mut new_description);
-                    if request_client
-                        .patch(format!(
-                            "http://{}:{}/reminders/{}/description",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .json(&new_description)
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(
+                        request_client
+                            .patch(format!(
+                                "http://{}:{}/reminders/{}/description",
+                                config.network().remote_ip(),
+                                config.network().port(),
+                                selected_reminder.id()
+                            ))
+                            .json(&new_description),
+                    )?;
+                    Ok(true)
                 }
                 KeyCode::Char('+') => {
-                    let _trash_bin = stdout.write_all(b"Add duration (1h10m15s): ");
+                    let duration = read_duration(stdout, "Add duration (1h10m15s | 3 days): ")?;
+                    send(
+                        request_client
+                            .patch(format!(
+                                "http://{}:{}/reminders/{}/push_duration",
+                                config.network().remote_ip(),
+                                config.network().port(),
+                                selected_reminder.id()
+                            ))
+                            .json(&duration),
+                    )?;
+                    Ok(true)
+                }
+                KeyCode::Char('-') => {
+                    let duration =
+                        read_duration(stdout, "Subtract duration (1h10m15s | 3 days): ")?;
+                    send(
+                        request_client
+                            .patch(format!(
+                                "http://{}:{}/reminders/{}/cut_duration",
+                                config.network().remote_ip(),
+                                config.network().port(),
+                                selected_reminder.id()
+                            ))
+                            .json(&duration),
+                    )?;
+                    Ok(true)
+                }
+                KeyCode::Char('f') => {
                     execute!(stdout, cursor::Show,).unwrap();
-                    let _trash_bin = disable_raw_mode().is_ok();
-                    let mut time_input = String::new();
-                    let _trash_bin = stdin().read_line(&mut time_input);
-                    time_input = time_input.replace('\n', "");
-
+                    let mut tag_input = String::new();
                     let _trash_bin = enable_raw_mode().is_ok();
-                    let Ok(parsed_duration) = DurationString::from_string(time_input) else {
-                        return false;
+                    let _trash_bin = stdout.write_all(b"Show only tag (#work | empty shows all): ");
+                    let _trash_bin = stdout.flush();
+                    let _trash_bin = disable_raw_mode().is_ok();
+                    if stdin().read_line(&mut tag_input).is_err() {
+                        return Ok(false);
+                    }
+                    let new_filter = if tag_input.trim().is_empty() {
+                        None
+                    } else {
+                        let Some(tag) = normalize_tag(tag_input.as_str()) else {
+                            return Err(invalid_input_error(tag_input.trim(), "a tag"));
+                        };
+                        Some(tag)
                     };
-                    let duration: core::time::Duration = parsed_duration.into();
-                    if request_client
-                        .patch(format!(
-                            "http://{}:{}/reminders/{}/push_duration",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .json(&duration)
-                        .send()
-                        .is_ok()
+                    *tag_filter = new_filter;
+                    *cursor_position = 0;
+                    selection.clear();
+                    Ok(false)
+                }
+                KeyCode::Char('x') if reminder_amount > 0 => {
+                    edit_checklist(stdout, selected_reminder.id(), request_client, config)?;
+                    Ok(true)
+                }
+                KeyCode::Char('C') => {
+                    let _trash_bin = execute!(stdout, cursor::Show,);
+                    let mut chain_input = String::new();
+                    let _trash_bin = enable_raw_mode().is_ok();
+                    let _trash_bin = stdout.write_all(b"Chain (preheat 10m, bake 25m, rest 5m): ");
+                    let _trash_bin = stdout.flush();
+                    let _trash_bin = disable_raw_mode().is_ok();
+                    if stdin().read_line(&mut chain_input).is_err() {
+                        return Ok(false);
+                    }
+                    let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+                    let mut chain: Vec<ApiReminder> = vec![];
+                    for step in chain_input
+                        .split(',')
+                        .filter(|step| !step.trim().is_empty())
                     {
-                        return true;
+                        let Some((name, duration)) = parse_chain_step(step) else {
+                            return Err(invalid_input_error(step.trim(), "a name and a duration"));
+                        };
+                        let (name, mut tags) = split_tags(name.as_str());
+                        if tags.is_empty() {
+                            tags.extend(tag_filter.clone());
+                        }
+                        chain.push(ApiReminder {
+                            name,
+                            description: String::new(),
+                            finish_time: now.saturating_add(duration),
+                            reminder_type: ReminderType::Duration,
+                            tags,
+                            priority: Priority::Normal,
+                            checklist: vec![],
+                        });
                     }
-                    return false;
+                    send(
+                        request_client
+                            .post(format!(
+                                "http://{}:{}/reminders/chain",
+                                config.network().remote_ip(),
+                                config.network().port()
+                            ))
+                            .json(&chain),
+                    )?;
+                    Ok(true)
                 }
-                KeyCode::Char('-') => {
-                    let _trash_bin = stdout.write_all(b"Subtract duration (1h10m15s): ");
-                    execute!(stdout, cursor::Show,).unwrap();
-                    let _trash_bin = disable_raw_mode().is_ok();
-                    let mut time_input = String::new();
-                    let _trash_bin = stdin().read_line(&mut time_input);
-                    time_input = time_input.replace('\n', "");
-
+                KeyCode::Char('P') => {
+                    let _trash_bin = execute!(stdout, cursor::Show,);
+                    let mut name = String::new();
                     let _trash_bin = enable_raw_mode().is_ok();
-                    let Ok(parsed_duration) = DurationString::from_string(time_input) else {
-                        return false;
-                    };
-                    let duration: core::time::Duration = parsed_duration.into();
-                    if request_client
-                        .patch(format!(
-                            "http://{}:{}/reminders/{}/cut_duration",
-                            config.network().remote_ip(),
-                            config.network().port(),
-                            selected_reminder.id()
-                        ))
-                        .json(&duration)
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
+                    let _trash_bin = stdout.write_all(b"Pomodoro (name | empty for pomodoro): ");
+                    let _trash_bin = stdout.flush();
+                    let _trash_bin = disable_raw_mode().is_ok();
+                    if stdin().read_line(&mut name).is_err() {
+                        return Ok(false);
+                    }
+                    let pomodoro_request = request_client.post(format!(
+                        "http://{}:{}/pomodoro",
+                        config.network().remote_ip(),
+                        config.network().port()
+                    ));
+                    if name.trim().is_empty() {
+                        send(pomodoro_request)?;
+                    } else {
+                        send(pomodoro_request.json(&name.trim()))?;
+                    }
+                    Ok(true)
+                }
+                KeyCode::Char('o') => {
+                    *sort_order = sort_order.next();
+                    Ok(false)
+                }
+                KeyCode::Char('A') => {
+                    browse_archive(stdout, request_client, config)?;
+                    Ok(true)
+                }
+                KeyCode::Char('I') => {
+                    show_stats(stdout, request_client, config)?;
+                    Ok(true)
+                }
+                KeyCode::Char('v') => {
+                    selection.toggle_range(all_reminders, *cursor_position);
+                    Ok(false)
+                }
+                KeyCode::Char('V') => {
+                    if reminder_amount > 0 {
+                        selection.toggle_mark(selected_reminder.id());
                     }
-                    return false;
+                    Ok(false)
                 }
                 KeyCode::Char(':') => {
                     if let Ok(Event::Key(event)) = read() {
@@ -398,49 +502,98 @@ pub fn read_input(
                             std::process::exit(0);
                         }
                     }
-                    return false;
+                    return Ok(false);
                 }
                 KeyCode::Esc => {
-                    if request_client
-                        .put(format!(
-                            "http://{}:{}/reminders/reset_flags",
-                            config.network().remote_ip(),
-                            config.network().port()
-                        ))
-                        .send()
-                        .is_ok()
-                    {
-                        return true;
-                    }
-                    return false;
+                    send(request_client.put(format!(
+                        "http://{}:{}/reminders/reset_flags",
+                        config.network().remote_ip(),
+                        config.network().port()
+                    )))?;
+                    Ok(true)
                 }
-                _ => return false,
+                _ => Ok(false),
             };
         }
-        false
+        Ok(false)
     } else {
-        false
+        Ok(false)
     }
 }
+/// Applies the batch actions to the reminders selected in visual mode, all at once.
+/// Returns `None` for keys without a batch action, so they keep their usual meaning.
+fn read_batch_input(
+    stdout: &mut Stdout,
+    event: &KeyEvent,
+    ids: Vec<usize>,
+    selection: &mut Selection,
+    request_client: &reqwest::blocking::Client,
+    config: &Config,
+) -> Option<Result<bool, ApiError>> {
+    #[allow(clippy::wildcard_enum_match_arm)]
+    let (action, duration) = match event.code {
+        KeyCode::Esc => {
+            selection.clear();
+            return Some(Ok(false));
+        }
+        KeyCode::Char('d') => ("delete", None),
+        KeyCode::Char(' ') => ("pause", None),
+        KeyCode::Char('s') => ("snooze", None),
+        KeyCode::Char('c') if !event.modifiers.contains(KeyModifiers::CONTROL) => ("confirm", None),
+        KeyCode::Char('S') => (
+            "snooze",
+            Some(read_duration(stdout, "Snooze for (10m | 1h | 2 hours): ")),
+        ),
+        KeyCode::Char('+') => (
+            "push_duration",
+            Some(read_duration(stdout, "Add duration (1h10m15s | 3 days): ")),
+        ),
+        KeyCode::Char('-') => (
+            "cut_duration",
+            Some(read_duration(
+                stdout,
+                "Subtract duration (1h10m15s | 3 days): ",
+            )),
+        ),
+        _ => return None,
+    };
+    let duration = match duration.transpose() {
+        Ok(duration) => duration,
+        Err(error) => return Some(Err(error)),
+    };
+    let result = send(
+        request_client
+            .put(format!(
+                "http://{}:{}/reminders/batch/{action}",
+                config.network().remote_ip(),
+                config.network().port()
+            ))
+            .json(&BatchRequest { ids, duration }),
+    );
+    if result.is_ok() {
+        selection.clear();
+    }
+    Some(result.map(|()| true))
+}
+
 #[allow(clippy::too_many_lines)]
 fn read_re_mode_input(
     stdout: &mut Stdout,
     selected_reminder: &Reminder,
+    all_reminders: &[Reminder],
     request_client: &reqwest::blocking::Client,
     config: &Config,
-) {
+) -> Result<(), ApiError> {
     if let Ok(Event::Key(event)) = read() {
         #[allow(clippy::wildcard_enum_match_arm)]
         match event.code {
             KeyCode::Char('s') => {
-                let _ = request_client
-                    .put(format!(
-                        "http://{}:{}/reminders/{}/restart",
-                        config.network().remote_ip(),
-                        config.network().port(),
-                        selected_reminder.id()
-                    ))
-                    .send();
+                send(request_client.put(format!(
+                    "http://{}:{}/reminders/{}/restart",
+                    config.network().remote_ip(),
+                    config.network().port(),
+                    selected_reminder.id()
+                )))?;
             }
             KeyCode::Char('n') => {
                 let _trash_bin = stdout.write_all(b"New name: ");
@@ -449,107 +602,253 @@ fn read_re_mode_input(
                 let mut name = String::new();
                 let _trash_bin = stdin().read_line(&mut name);
                 name = name.replace('\n', "");
-                let _ = request_client
-                    .patch(format!(
-                        "http://{}:{}/reminders/{}/rename",
-                        config.network().remote_ip(),
-                        config.network().port(),
-                        selected_reminder.id()
-                    ))
-                    .json(&name)
-                    .send();
+                send(
+                    request_client
+                        .patch(format!(
+                            "http://{}:{}/reminders/{}/rename",
+                            config.network().remote_ip(),
+                            config.network().port(),
+                            selected_reminder.id()
+                        ))
+                        .json(&name),
+                )?;
             }
-            KeyCode::Char('t') => {
-                let _trash_bin = stdout.write_all(
-                    b"New end time or date (1h10m | 15:23 | 8.11.2023 | 8.11.2023 15:23): ",
-                );
-                execute!(stdout, cursor::Show,).unwrap();
+            KeyCode::Char('c') => {
+                send(request_client.put(format!(
+                    "http://{}:{}/reminders/{}/start_chain",
+                    config.network().remote_ip(),
+                    config.network().port(),
+                    selected_reminder.id()
+                )))?;
+            }
+            KeyCode::Char('l') => {
+                let _trash_bin = stdout.write_all(b"Then start (name | empty ends the chain): ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut name = String::new();
+                let _trash_bin = stdin().read_line(&mut name);
+                let _trash_bin = enable_raw_mode().is_ok();
+                let name = name.trim().to_lowercase();
+                let next_id = if name.is_empty() {
+                    None
+                } else {
+                    let Some(next) = all_reminders.iter().find(|reminder| {
+                        reminder.id() != selected_reminder.id()
+                            && reminder.name().to_lowercase().starts_with(name.as_str())
+                    }) else {
+                        return Err(invalid_input_error(&name, "the name of another reminder"));
+                    };
+                    Some(next.id())
+                };
+                send(
+                    request_client
+                        .put(format!(
+                            "http://{}:{}/reminders/{}/next",
+                            config.network().remote_ip(),
+                            config.network().port(),
+                            selected_reminder.id()
+                        ))
+                        .json(&next_id),
+                )?;
+            }
+            KeyCode::Char('p') => {
+                let _trash_bin = stdout.write_all(b"Priority (low | normal | high): ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut priority_input = String::new();
+                let _trash_bin = stdin().read_line(&mut priority_input);
+                let _trash_bin = enable_raw_mode().is_ok();
+                let Ok(priority) = priority_input.parse::<Priority>() else {
+                    return Err(invalid_input_error(priority_input.trim(), "a priority"));
+                };
+                send(
+                    request_client
+                        .patch(format!(
+                            "http://{}:{}/reminders/{}/priority",
+                            config.network().remote_ip(),
+                            config.network().port(),
+                            selected_reminder.id()
+                        ))
+                        .json(&priority),
+                )?;
+            }
+            KeyCode::Char('#') => {
+                let _trash_bin = stdout.write_all(b"Tags (#work #home | empty for none): ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut tag_input = String::new();
+                let _trash_bin = stdin().read_line(&mut tag_input);
+                send(
+                    request_client
+                        .patch(format!(
+                            "http://{}:{}/reminders/{}/tags",
+                            config.network().remote_ip(),
+                            config.network().port(),
+                            selected_reminder.id()
+                        ))
+                        .json(&parse_tags(tag_input.as_str())),
+                )?;
+            }
+            KeyCode::Char('z') => {
+                let _trash_bin =
+                    stdout.write_all(b"Snooze duration (10m | 1h | empty for the default): ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
                 let _trash_bin = disable_raw_mode().is_ok();
                 let mut time_input = String::new();
                 let _trash_bin = stdin().read_line(&mut time_input);
                 time_input = time_input.replace('\n', "");
-                let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
-                let mut finish_time = OffsetDateTime::now_utc().to_offset(my_local_offset());
-
                 let _trash_bin = enable_raw_mode().is_ok();
-                #[allow(clippy::useless_conversion)]
-                if time_input.chars().all(|e| e.is_ascii_digit() || e == ':') {
-                    let Ok(format) = &format_description::parse("[hour]:[minute]") else {
-                        return;
-                    };
-                    let Ok(new_finish_time) = Time::parse(time_input.as_str(), format) else {
-                        return;
+                let snooze_duration = if time_input.trim().is_empty() {
+                    None
+                } else {
+                    let Some(duration) = parse_duration(time_input.as_str())
+                        .and_then(|duration| core::time::Duration::try_from(duration).ok())
+                    else {
+                        return Err(invalid_input_error(&time_input, "a duration"));
                     };
-                    finish_time = finish_time.replace_time(new_finish_time);
-                    #[allow(clippy::arithmetic_side_effects)]
-                    let _ = request_client
+                    Some(duration)
+                };
+                send(
+                    request_client
                         .patch(format!(
-                            "http://{}:{}/reminders/{}/retime",
+                            "http://{}:{}/reminders/{}/snooze_duration",
                             config.network().remote_ip(),
                             config.network().port(),
                             selected_reminder.id()
                         ))
-                        .json(&TimeObject {
-                            reminder_type: ReminderType::Time,
-                            finish_time,
-                            duration: finish_time - now,
-                        })
-                        .send();
-                } else if time_input
-                    .chars()
-                    .all(|e| e.is_ascii_digit() || e == '.' || e == ':' || e == ' ')
+                        .json(&snooze_duration),
+                )?;
+            }
+            KeyCode::Char('w') => {
+                let _trash_bin = stdout.write_all(b"Warn before (15m, 1m | empty for none): ");
+                let _trash_bin = execute!(stdout, cursor::Show,);
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut time_input = String::new();
+                let _trash_bin = stdin().read_line(&mut time_input);
+                time_input = time_input.replace('\n', "");
+                let _trash_bin = enable_raw_mode().is_ok();
+                let mut warnings: Vec<core::time::Duration> = vec![];
+                for lead_time in time_input
+                    .split(',')
+                    .filter(|input| !input.trim().is_empty())
                 {
-                    let Ok(format) =
-                        format_description::parse("[day].[month].[year] [hour]:[minute]")
-                    else {
-                        return;
-                    };
-                    let Ok(new_finish_date_time) =
-                        PrimitiveDateTime::parse(time_input.as_str(), &format)
+                    let Some(duration) = parse_duration(lead_time)
+                        .and_then(|duration| core::time::Duration::try_from(duration).ok())
                     else {
-                        return;
+                        return Err(invalid_input_error(lead_time.trim(), "a duration"));
                     };
-                    finish_time = finish_time.replace_date(new_finish_date_time.date());
-                    finish_time = finish_time.replace_time(new_finish_date_time.time());
-                    #[allow(clippy::arithmetic_side_effects)]
-                    let _ = request_client
+                    warnings.push(duration);
+                }
+                send(
+                    request_client
                         .patch(format!(
-                            "http://{}:{}/reminders/{}/retime",
+                            "http://{}:{}/reminders/{}/warnings",
                             config.network().remote_ip(),
                             config.network().port(),
                             selected_reminder.id()
                         ))
-                        .json(&TimeObject {
-                            reminder_type: ReminderType::Date,
-                            finish_time,
-                            duration: finish_time - now,
-                        })
-                        .send();
+                        .json(&warnings),
+                )?;
+            }
+            KeyCode::Char('t') => {
+                let _trash_bin = stdout.write_all(
+                    format!("New end time or date ({TIME_INPUT_EXAMPLES}): ").as_bytes(),
+                );
+                execute!(stdout, cursor::Show,).unwrap();
+                let _trash_bin = disable_raw_mode().is_ok();
+                let mut time_input = String::new();
+                let _trash_bin = stdin().read_line(&mut time_input);
+                time_input = time_input.replace('\n', "");
+                let _trash_bin = stdout.write_all(
+                    b"Repeat (empty keeps current | restart | 2h | 3d | mon,fri | monthly 15 | yearly): ",
+                );
+                let _trash_bin = stdout.flush();
+                let mut recurrence_input = String::new();
+                let _trash_bin = stdin().read_line(&mut recurrence_input);
+                let recurrence = if recurrence_input.trim().is_empty() {
+                    None
                 } else {
-                    let Ok(parsed_duration) = DurationString::from_string(time_input) else {
-                        return;
-                    };
-                    let d: core::time::Duration = parsed_duration.into();
-                    let Ok(duration) = d.try_into() else {
-                        return;
+                    let Ok(recurrence) = recurrence_input.parse::<Recurrence>() else {
+                        return Err(invalid_input_error(
+                            recurrence_input.trim(),
+                            "a repeat rule",
+                        ));
                     };
-                    #[allow(clippy::arithmetic_side_effects)]
-                    let _ = request_client
+                    Some(recurrence)
+                };
+                let _trash_bin = enable_raw_mode().is_ok();
+                let Ok(mut time_object) = parse_time_input(
+                    time_input.as_str(),
+                    OffsetDateTime::now_utc().to_offset(my_local_offset()),
+                ) else {
+                    return Err(invalid_input_error(&time_input, TIME_INPUT_KINDS));
+                };
+                time_object.recurrence = recurrence;
+                send(
+                    request_client
                         .patch(format!(
                             "http://{}:{}/reminders/{}/retime",
                             config.network().remote_ip(),
                             config.network().port(),
                             selected_reminder.id()
                         ))
-                        .json(&TimeObject {
-                            reminder_type: ReminderType::Duration,
-                            finish_time: now + d,
-                            duration,
-                        })
-                        .send();
-                }
+                        .json(&time_object),
+                )?;
             }
             _ => (),
         }
     }
+    Ok(())
+}
+
+/// Sends `request` and turns every failure into an `ApiError`.
+pub fn send(request: RequestBuilder) -> Result<(), ApiError> {
+    checked_response(request).map(|_| ())
+}
+
+/// Sends `request` and reads the json it answers with.
+pub fn fetch<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ApiError> {
+    checked_response(request)?
+        .json()
+        .map_err(|error| ApiError::internal(format!("Could not read the answer: {error}")))
+}
+
+fn checked_response(request: RequestBuilder) -> Result<Response, ApiError> {
+    let response = request
+        .send()
+        .map_err(|error| ApiError::new(ApiErrorCode::ServerUnreachable, error.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    Err(response
+        .json()
+        .unwrap_or_else(|_| ApiError::internal(format!("The server answered with {status}"))))
+}
+
+/// Reads one step of a chain like `preheat 10m` or `bake 25 minutes`, the duration comes last.
+fn parse_chain_step(step: &str) -> Option<(String, time::Duration)> {
+    let words: Vec<&str> = step.split_whitespace().collect();
+    (1..words.len()).find_map(|split| {
+        let (name, duration) = words.split_at(split);
+        parse_duration(duration.join(" ").as_str()).map(|duration| (name.join(" "), duration))
+    })
+}
+
+/// Prompts for a duration like `1h10m15s` or `3 days`.
+fn read_duration(stdout: &mut Stdout, prompt: &str) -> Result<core::time::Duration, ApiError> {
+    let _trash_bin = stdout.write_all(prompt.as_bytes());
+    let _trash_bin = execute!(stdout, cursor::Show,);
+    let _trash_bin = disable_raw_mode().is_ok();
+    let mut time_input = String::new();
+    let _trash_bin = stdin().read_line(&mut time_input);
+    time_input = time_input.replace('\n', "");
+    let _trash_bin = enable_raw_mode().is_ok();
+    parse_duration(time_input.as_str())
+        .and_then(|duration| core::time::Duration::try_from(duration).ok())
+        .ok_or_else(|| invalid_input_error(&time_input, "a duration"))
+}
+
+fn invalid_input_error(input: &str, expected: &str) -> ApiError {
+    ApiError::invalid_input(format!("Could not read '{input}' as {expected}"))
 }
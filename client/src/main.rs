@@ -1,12 +1,9 @@
 #[cfg(feature = "music")]
 use std::fs::File;
-#[cfg(feature = "music")]
-use std::io::BufReader;
 #[cfg(any(feature = "i3", target_os = "macos"))]
 use std::process::{Command, Stdio};
 use std::{
-    cmp::Ordering,
-    io::Write,
+    io::{BufRead, BufReader, Write},
     sync::{Arc, Mutex},
     thread,
     time::Duration,
@@ -17,9 +14,20 @@ use crossterm::{
     cursor, execute,
     terminal::{self, enable_raw_mode},
 };
-use reminder::{past_event::PastEvent, reminder::Reminder};
+use reminder::{
+    api_error::ApiError,
+    history::HistoryPreview,
+    library::ReminderTemplate,
+    past_event::{LoggedEvent, EVENT_LOG_LENGTH},
+    priority::{Priority, SortOrder},
+    reminder::Reminder,
+    stream_event::StreamEvent,
+    time_input::format_duration,
+    timezone::init_timezone,
+};
 #[cfg(feature = "music")]
 use reminder::{root_path, AUDIO_FILE};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 
 mod status_box;
 use rodio::OutputStream;
@@ -28,34 +36,80 @@ use rodio::{Decoder, Sink};
 use status_box::build_status_box;
 
 mod reminders;
-use reminders::build_reminder_list;
+use reminders::{build_reminder_list, group_chains, visible_reminders};
 
 mod key_reader;
 use key_reader::read_input;
 
+mod selection;
+use selection::Selection;
+
+mod archive_view;
+
+mod checklist_view;
+
+mod stats_view;
+
+#[allow(clippy::too_many_lines)]
 pub fn main() {
     let config = Arc::new(Config::new());
     let mut cursor_position: usize = 0;
     let mut stdout = std::io::stdout();
-    let request_client = reqwest::blocking::Client::new();
+    let Ok(request_client) = authenticated_client(&config).build() else {
+        return;
+    };
     let reminders: Arc<Mutex<Vec<Reminder>>> = Arc::new(Mutex::new(vec![]));
-    let past_event: Arc<Mutex<PastEvent>> = Arc::new(Mutex::new(PastEvent::None));
+    let mut event_scroll: usize = 0;
+    let mut selection = Selection::default();
+    let mut tag_filter: Option<String> = None;
+    let mut sort_order = config.sort_order();
+    let mut last_error: Option<ApiError> = init_timezone(config.timezone())
+        .err()
+        .map(ApiError::invalid_input);
+    let events: Arc<Mutex<Vec<LoggedEvent>>> = Arc::new(Mutex::new(vec![]));
+    let library: Arc<Mutex<Vec<ReminderTemplate>>> = Arc::new(Mutex::new(vec![]));
+    let history: Arc<Mutex<HistoryPreview>> = Arc::new(Mutex::new(HistoryPreview::default()));
     spawn_async_reminder_fetch(
         Arc::clone(&reminders),
-        Arc::clone(&past_event),
+        Arc::clone(&events),
+        Arc::clone(&library),
+        Arc::clone(&history),
         Arc::clone(&config),
     );
     loop {
-        let Ok(locked_past_event) = past_event.lock() else {
+        let Ok(locked_events) = events.lock() else {
+            return;
+        };
+        let Ok(locked_library) = library.lock() else {
             return;
         };
-        let status_box = build_status_box(&locked_past_event);
+        let Ok(locked_history) = history.lock() else {
+            return;
+        };
+        let status_box = build_status_box(
+            &locked_events,
+            event_scroll,
+            &locked_library,
+            &locked_history,
+            last_error.as_ref(),
+        );
         let status_box = status_box.as_bytes();
-        drop(locked_past_event);
+        drop(locked_events);
+        drop(locked_library);
+        drop(locked_history);
 
         let mut should_fetch_data = false;
-        if let Ok(mut reminders) = reminders.lock() {
-            let reminder_list = build_reminder_list(&mut reminders, cursor_position);
+        if let Ok(locked_reminders) = reminders.lock() {
+            let mut reminders = visible_reminders(&locked_reminders, tag_filter.as_deref());
+            drop(locked_reminders);
+            sort_reminders(&mut reminders, sort_order);
+            let mut reminders = group_chains(reminders);
+            let reminder_list = build_reminder_list(
+                &mut reminders,
+                cursor_position,
+                &selection,
+                tag_filter.as_deref(),
+            );
             let reminder_list = reminder_list.as_bytes();
             let _trash_bin = enable_raw_mode().is_ok();
             execute!(
@@ -71,108 +125,310 @@ pub fn main() {
             if stdout.write_all(reminder_list).is_err() {
                 return;
             }
-            if let Some(selected_reminder) = reminders.get(cursor_position) {
-                should_fetch_data = read_input(
+            let input_result = if let Some(selected_reminder) = reminders.get(cursor_position) {
+                read_input(
                     &mut stdout,
                     selected_reminder,
                     &reminders,
                     reminders.len(),
                     &mut cursor_position,
+                    &mut event_scroll,
                     &request_client,
                     &config,
-                );
+                    &mut selection,
+                    &mut tag_filter,
+                    &mut sort_order,
+                )
             } else {
-                should_fetch_data = read_input(
+                read_input(
                     &mut stdout,
                     &Reminder::default(),
                     &reminders,
                     reminders.len(),
                     &mut cursor_position,
+                    &mut event_scroll,
                     &request_client,
                     &config,
-                );
+                    &mut selection,
+                    &mut tag_filter,
+                    &mut sort_order,
+                )
+            };
+            match input_result {
+                Ok(fetch_data) => {
+                    if fetch_data {
+                        last_error = None;
+                    }
+                    should_fetch_data = fetch_data;
+                }
+                Err(error) => {
+                    last_error = Some(error);
+                    should_fetch_data = true;
+                }
             }
         }
         if should_fetch_data {
-            fetch_data(&reminders, &past_event, &config);
+            fetch_data(&reminders, &events, &library, &history, &config);
         }
     }
 }
 
 fn spawn_async_reminder_fetch(
     reminders: Arc<Mutex<Vec<Reminder>>>,
-    past_event: Arc<Mutex<PastEvent>>,
+    events: Arc<Mutex<Vec<LoggedEvent>>>,
+    library: Arc<Mutex<Vec<ReminderTemplate>>>,
+    history: Arc<Mutex<HistoryPreview>>,
     config: Arc<Config>,
 ) {
+    let preview_config = Arc::clone(&config);
+    let preview_library = Arc::clone(&library);
+    let preview_history = Arc::clone(&history);
+    // The stream only carries reminders and events, so the library and history previews keep being polled
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(5));
+        fetch_previews(&preview_library, &preview_history, &preview_config);
+    });
     thread::spawn(move || loop {
-        fetch_data(&reminders, &past_event, &config);
+        register_client(&config);
+        fetch_data(&reminders, &events, &library, &history, &config);
+        // This only returns once the stream is unavailable, so we fall back to polling until it is back
+        consume_event_stream(&reminders, &events, &config);
         thread::sleep(Duration::from_secs(5));
     });
 }
 
+/// Applies every event of the servers `/stream` to the local state until the connection drops.
+fn consume_event_stream(
+    reminders: &Mutex<Vec<Reminder>>,
+    events: &Mutex<Vec<LoggedEvent>>,
+    config: &Config,
+) {
+    let Ok(stream_client) = authenticated_client(config).timeout(None).build() else {
+        return;
+    };
+    let Ok(response) = stream_client
+        .get(format!(
+            "http://{}:{}/stream",
+            config.network().remote_ip(),
+            config.network().port()
+        ))
+        .send()
+    else {
+        return;
+    };
+    if !response.status().is_success() {
+        return;
+    }
+    for line in BufReader::new(response).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let Ok(stream_event) = serde_json::from_str::<StreamEvent>(data.trim()) else {
+            continue;
+        };
+        if let StreamEvent::Event(new_event) = stream_event {
+            if let Ok(mut events) = events.lock() {
+                append_events(&mut events, vec![new_event]);
+            }
+            continue;
+        }
+        let Ok(mut locked_reminders) = reminders.lock() else {
+            return;
+        };
+        let changed_reminders = stream_event.apply(&mut locked_reminders);
+        drop(locked_reminders);
+        alert_due_reminders(&changed_reminders, config);
+    }
+}
+
 fn fetch_data(
     reminders: &Mutex<Vec<Reminder>>,
-    past_event: &Arc<Mutex<PastEvent>>,
-    config: &Arc<Config>,
+    events: &Mutex<Vec<LoggedEvent>>,
+    library: &Mutex<Vec<ReminderTemplate>>,
+    history: &Mutex<HistoryPreview>,
+    config: &Config,
 ) {
-    let request_client = reqwest::blocking::Client::new();
+    let Ok(request_client) = authenticated_client(config).build() else {
+        return;
+    };
     let mut new_reminders: Vec<Reminder> = vec![];
-    if let Ok(response) = reqwest::blocking::get(format!(
-        "http://{}:{}/reminders",
-        config.network().remote_ip(),
-        config.network().port()
-    )) {
+    if let Ok(response) = request_client
+        .get(format!(
+            "http://{}:{}/reminders",
+            config.network().remote_ip(),
+            config.network().port()
+        ))
+        .send()
+    {
         if let Ok(data) = response.json() {
             new_reminders = data;
         }
     }
-    for reminder in &new_reminders {
-        if reminder.needs_confirmation() {
-            alert_user(reminder);
-            let _ = request_client
-                .put(format!(
-                    "http://{}:{}/reminders/{}/confirm",
-                    config.network().remote_ip(),
-                    config.network().port(),
-                    reminder.id()
-                ))
-                .send();
+    alert_due_reminders(&new_reminders, config);
+    if let Ok(mut reminders) = reminders.lock() {
+        *reminders = new_reminders;
+    }
+
+    fetch_previews(library, history, config);
+
+    let Ok(mut events) = events.lock() else {
+        return;
+    };
+    let Ok(response) = request_client
+        .get(format!(
+            "http://{}:{}/events?since={}",
+            config.network().remote_ip(),
+            config.network().port(),
+            events.last().map_or(0, LoggedEvent::id)
+        ))
+        .send()
+    else {
+        return;
+    };
+    let Ok(new_events) = response.json() else {
+        return;
+    };
+    append_events(&mut events, new_events);
+}
+
+/// Fetches the template library and the labels of the next undo and redo.
+fn fetch_previews(
+    library: &Mutex<Vec<ReminderTemplate>>,
+    history: &Mutex<HistoryPreview>,
+    config: &Config,
+) {
+    let Ok(request_client) = authenticated_client(config).build() else {
+        return;
+    };
+    if let Ok(response) = request_client
+        .get(format!(
+            "http://{}:{}/library",
+            config.network().remote_ip(),
+            config.network().port()
+        ))
+        .send()
+    {
+        if let Ok(new_library) = response.json() {
+            if let Ok(mut library) = library.lock() {
+                *library = new_library;
+            }
         }
     }
-    new_reminders.sort_by(|a, b| {
-        if a.finish_time().cmp(&b.finish_time()) == Ordering::Less {
-            Ordering::Greater
-        } else {
-            Ordering::Less
+
+    if let Ok(response) = request_client
+        .get(format!(
+            "http://{}:{}/reminders/history",
+            config.network().remote_ip(),
+            config.network().port()
+        ))
+        .send()
+    {
+        if let Ok(new_history) = response.json() {
+            if let Ok(mut history) = history.lock() {
+                *history = new_history;
+            }
         }
-    });
-    if let Ok(mut reminders) = reminders.lock() {
-        *reminders = new_reminders;
     }
+}
+
+/// Appends events that are newer than the newest known one, so the stream and polling can overlap.
+fn append_events(events: &mut Vec<LoggedEvent>, new_events: Vec<LoggedEvent>) {
+    let last_id = events.last().map_or(0, LoggedEvent::id);
+    events.extend(new_events.into_iter().filter(|event| event.id() > last_id));
+    if events.len() > EVENT_LOG_LENGTH {
+        events.drain(..events.len().saturating_sub(EVENT_LOG_LENGTH));
+    }
+}
+
+/// A http client, that sends the token from the config with every request.
+fn authenticated_client(config: &Config) -> reqwest::blocking::ClientBuilder {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = config.network().token() {
+        if let Ok(mut value) = HeaderValue::from_str(format!("Bearer {token}").as_str()) {
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+    }
+    reqwest::blocking::Client::builder().default_headers(headers)
+}
 
-    let Ok(response) = reqwest::blocking::get(format!(
-        "http://{}:{}/past_event",
-        config.network().remote_ip(),
-        config.network().port()
-    )) else {
+/// Registers this client with the server, so due reminders keep alerting here until they are acknowledged.
+fn register_client(config: &Config) {
+    let Ok(request_client) = authenticated_client(config).build() else {
         return;
     };
-    let Ok(new_past_event) = response.json() else {
+    let _ = request_client
+        .put(format!(
+            "http://{}:{}/clients/{}",
+            config.network().remote_ip(),
+            config.network().port(),
+            config.network().client_id()
+        ))
+        .send();
+}
+
+/// Alerts about every reminder this client did not alert about yet, and tells the server it did.
+/// Acknowledging a reminder with 'c' is up to the user.
+fn alert_due_reminders(reminders: &[Reminder], config: &Config) {
+    let Ok(request_client) = authenticated_client(config).build() else {
         return;
     };
-    if let Ok(mut past_event) = past_event.lock() {
-        *past_event = new_past_event;
+    let client_id = config.network().client_id();
+    for reminder in reminders {
+        if !reminder.alert_pending(&client_id) {
+            continue;
+        }
+        if reminder.needs_confirmation() {
+            match reminder.pomodoro() {
+                Some(pomodoro) => alert_user(
+                    format!("{}: {pomodoro}", reminder.name().trim_end()).as_str(),
+                    reminder.priority(),
+                ),
+                None => alert_user(reminder.name(), reminder.priority()),
+            }
+        } else if let Some(lead_time) = reminder.warning_needs_confirmation() {
+            alert_user(
+                format!(
+                    "{} in {}",
+                    reminder.name().trim_end(),
+                    format_duration(lead_time)
+                )
+                .as_str(),
+                reminder.priority(),
+            );
+        }
+        let _ = request_client
+            .put(format!(
+                "http://{}:{}/reminders/{}/delivered",
+                config.network().remote_ip(),
+                config.network().port(),
+                reminder.id()
+            ))
+            .json(&client_id)
+            .send();
     }
 }
 
-fn alert_user(reminder: &Reminder) {
+/// Sorts the reminders in `sort_order`, notes always come first.
+fn sort_reminders(reminders: &mut [Reminder], sort_order: SortOrder) {
+    sort_order.sort(reminders);
+    reminders.sort_by_key(|reminder| !reminder.is_note());
+}
+
+/// Low priority reminders only show the message, high priority ones play the sound at full volume.
+fn alert_user(message: &str, priority: Priority) {
     #[cfg(not(any(feature = "music", feature = "i3", feature = "msg_box")))]
-    let _ = reminder;
+    let _ = message;
+    #[cfg(not(any(feature = "music", feature = "i3")))]
+    let _ = priority;
     if let Ok((_stream, audio_stream_handle)) = OutputStream::try_default() {
         #[cfg(not(any(feature = "music", feature = "i3", feature = "msg_box")))]
         let _ = audio_stream_handle;
         #[cfg(feature = "music")]
-        {
+        if priority != Priority::Low {
             let Ok(file) = File::open(format!(
                 "{}/{AUDIO_FILE}",
                 format!("{:?}", root_path().unwrap()).replace('"', "")
@@ -187,26 +443,28 @@ fn alert_user(reminder: &Reminder) {
                 return;
             };
             sink.append(audio_source);
-            sink.set_volume(0.7);
+            sink.set_volume(if priority == Priority::High { 1.0 } else { 0.7 });
         }
 
         #[cfg(feature = "msg_box")]
-        let _trash_bin = msgbox::create(reminder.name(), "", msgbox::IconType::Info);
+        let _trash_bin = msgbox::create(message, "", msgbox::IconType::Info);
 
         #[cfg(feature = "i3")]
-        let _ = Command::new("i3-msg")
-            .arg("workspace")
-            // TODO: `musik` should be configurable
-            .arg("musik")
-            .stdout(Stdio::null())
-            .spawn();
+        if priority != Priority::Low {
+            let _ = Command::new("i3-msg")
+                .arg("workspace")
+                // TODO: `musik` should be configurable
+                .arg("musik")
+                .stdout(Stdio::null())
+                .spawn();
+        }
 
         #[cfg(all(target_os = "macos", feature = "msg_box"))]
         let _ = Command::new("osascript")
             .arg("-e")
             .arg(format!(
                 "display notification \"{}\" sound name \"Bottle\"",
-                reminder.name()
+                message
             ))
             .stdout(Stdio::null())
             .spawn();
@@ -3,10 +3,81 @@ use std::cmp::Ordering;
 use colored::Colorize;
 use time::format_description;
 
-use reminder::reminder::Reminder;
+use reminder::{
+    chain::{chain_ids, previous_in_chain},
+    recurrence::Recurrence,
+    reminder::Reminder,
+    timezone::to_local,
+};
 
-pub fn build_reminder_list(reminders: &mut [Reminder], cursor_position: usize) -> String {
+use crate::selection::Selection;
+
+/// The reminders shown in the list, only the ones tagged with `tag_filter` if it is set.
+#[must_use]
+pub fn visible_reminders(reminders: &[Reminder], tag_filter: Option<&str>) -> Vec<Reminder> {
+    reminders
+        .iter()
+        .filter(|reminder| tag_filter.is_none_or(|tag| reminder.has_tag(tag)))
+        .cloned()
+        .collect()
+}
+
+/// Moves the reminders of every chain right behind its first one, in the order they run.
+#[must_use]
+pub fn group_chains(reminders: Vec<Reminder>) -> Vec<Reminder> {
+    let mut grouped: Vec<Reminder> = Vec::with_capacity(reminders.len());
+    for reminder in &reminders {
+        if previous_in_chain(&reminders, reminder.id()).is_some() {
+            continue;
+        }
+        for id in chain_ids(&reminders, reminder.id()) {
+            if grouped.iter().any(|grouped| grouped.id() == id) {
+                break;
+            }
+            if let Some(link) = reminders.iter().find(|link| link.id() == id) {
+                grouped.push(link.clone());
+            }
+        }
+    }
+    // chains, that loop back onto themselves, have no first reminder
+    for reminder in reminders {
+        if !grouped.iter().any(|grouped| grouped.id() == reminder.id()) {
+            grouped.push(reminder);
+        }
+    }
+    grouped
+}
+
+#[allow(clippy::too_many_lines)]
+pub fn build_reminder_list(
+    reminders: &mut [Reminder],
+    cursor_position: usize,
+    selection: &Selection,
+    tag_filter: Option<&str>,
+) -> String {
     let mut result = String::new();
+    if let Some(tag) = tag_filter {
+        result.push_str(
+            format!(
+                " -- TAG #{tag} ({} shown) -- 'f' + 'enter' shows everything\n\r",
+                reminders.len()
+            )
+            .bright_yellow()
+            .to_string()
+            .as_str(),
+        );
+    }
+    let selected_amount = selection.ids(reminders, cursor_position).len();
+    if selected_amount > 0 {
+        result.push_str(
+            format!(
+                " -- VISUAL ({selected_amount} selected) -- 'd', ' ', 's', 'S', '+', '-', 'c', 'esc'\n\r"
+            )
+            .bright_yellow()
+            .to_string()
+            .as_str(),
+        );
+    }
     let mut displaying_due = false;
     let Ok(time_format) = format_description::parse("[hour]:[minute]:[second]") else {
         return String::new();
@@ -18,9 +89,23 @@ pub fn build_reminder_list(reminders: &mut [Reminder], cursor_position: usize) -
             Ordering::Less
         }
     }) else {
-        return String::new();
+        return result;
     };
     let longest_name_length: usize = longest_name_reminder.name().len();
+    let names: Vec<(usize, String)> = reminders
+        .iter()
+        .map(|reminder| (reminder.id(), reminder.name().trim_end().to_string()))
+        .collect();
+    let name_of = |id: usize| {
+        names
+            .iter()
+            .find(|(reminder_id, _)| *reminder_id == id)
+            .map_or_else(|| format!("reminder {id}"), |(_, name)| name.clone())
+    };
+    let previous_ids: Vec<(usize, usize)> = reminders
+        .iter()
+        .filter_map(|reminder| Some((reminder.next_in_chain()?, reminder.id())))
+        .collect();
 
     for (i, reminder) in reminders.iter_mut().enumerate() {
         while reminder.name().len() < longest_name_length {
@@ -41,8 +126,65 @@ pub fn build_reminder_list(reminders: &mut [Reminder], cursor_position: usize) -
         for _ in 0..24 {
             whitespace_enhanced_reminder_description.pop();
         }
+        if let Some(next_id) = reminder.next_in_chain() {
+            whitespace_enhanced_reminder_description = format!(
+                "                        ⤷ then {}\n{whitespace_enhanced_reminder_description}",
+                name_of(next_id)
+            );
+        }
+        if reminder.waiting() {
+            if let Some((_, previous_id)) = previous_ids
+                .iter()
+                .find(|(next_id, _)| *next_id == reminder.id())
+            {
+                whitespace_enhanced_reminder_description = format!(
+                    "                        … waits for {}\n{whitespace_enhanced_reminder_description}",
+                    name_of(*previous_id)
+                );
+            }
+        }
+        if let Some(pomodoro) = reminder.pomodoro() {
+            whitespace_enhanced_reminder_description = format!(
+                "                        🍅 {pomodoro}\n{whitespace_enhanced_reminder_description}"
+            );
+        }
+        for item in reminder.checklist().iter().rev() {
+            whitespace_enhanced_reminder_description = format!(
+                "                        {item}\n{whitespace_enhanced_reminder_description}"
+            );
+        }
+        if reminder.repeating() && *reminder.recurrence() != Recurrence::Restart {
+            whitespace_enhanced_reminder_description = format!(
+                "                        ↻ {}\n{whitespace_enhanced_reminder_description}",
+                reminder.recurrence()
+            );
+        }
+        if reminder.needs_confirmation() || reminder.warning_needs_confirmation().is_some() {
+            let mut alert_state = String::from("! 'c' to acknowledge");
+            if reminder.nag_count() > 0 && reminder.needs_confirmation() {
+                alert_state.push_str(format!(", nagged {}x", reminder.nag_count()).as_str());
+            }
+            if !reminder.delivered_to().is_empty() {
+                alert_state.push_str(
+                    format!(", alerted on {}", reminder.delivered_to().join(", ")).as_str(),
+                );
+            }
+            whitespace_enhanced_reminder_description = format!(
+                "                        {alert_state}\n{whitespace_enhanced_reminder_description}"
+            );
+        } else if reminder.nag() {
+            whitespace_enhanced_reminder_description = format!(
+                "                        ! nags until acknowledged\n{whitespace_enhanced_reminder_description}"
+            );
+        }
+        if reminder.snooze_count() > 0 {
+            whitespace_enhanced_reminder_description = format!(
+                "                        z snoozed {}x\n{whitespace_enhanced_reminder_description}",
+                reminder.snooze_count()
+            );
+        }
         let time_left = reminder.remaining_duration();
-        if time_left.is_none() && !displaying_due {
+        if time_left.is_none() && !reminder.is_note() && !displaying_due {
             displaying_due = true;
             result.push_str(format!(" {:-<68}\n", "").blue().to_string().as_str());
         }
@@ -51,6 +193,8 @@ pub fn build_reminder_list(reminders: &mut [Reminder], cursor_position: usize) -
                 "\r {}{}{}\n\r{}",
                 if i == cursor_position {
                     String::from("[")
+                } else if selection.contains(i, reminder.id(), cursor_position) {
+                    "*".bright_yellow().to_string()
                 } else {
                     " ".to_string()
                 },
@@ -62,10 +206,11 @@ pub fn build_reminder_list(reminders: &mut [Reminder], cursor_position: usize) -
                 },
                 if let Some(time_left) = time_left {
                     if time_left.whole_days() > 0 {
-                        let Ok(finish_time) = reminder.finish_time().format(&time_format) else {
+                        let Ok(finish_time) = to_local(reminder.finish_time()).format(&time_format)
+                        else {
                             return String::new();
                         };
-                        let weekday = reminder.finish_time().weekday();
+                        let weekday = to_local(reminder.finish_time()).weekday();
                         format!(
                             "                        {} {}\n\r{}",
                             finish_time,
@@ -0,0 +1,50 @@
+use reminder::reminder::Reminder;
+
+/// The reminders picked in visual mode, that batch actions apply to instead of only the one under the cursor.
+#[derive(Default)]
+pub struct Selection {
+    /// The position where `v` started a range, that spans to the cursor.
+    anchor: Option<usize>,
+    /// Ids marked one by one with `V`, or by ending a range.
+    marked: Vec<usize>,
+}
+
+impl Selection {
+    /// Starts a range at the cursor, or ends the current one and keeps its reminders marked.
+    pub fn toggle_range(&mut self, reminders: &[Reminder], cursor_position: usize) {
+        if self.anchor.is_some() {
+            self.marked = self.ids(reminders, cursor_position);
+            self.anchor = None;
+        } else {
+            self.anchor = Some(cursor_position);
+        }
+    }
+    pub fn toggle_mark(&mut self, id: usize) {
+        if let Some(position) = self.marked.iter().position(|marked| *marked == id) {
+            self.marked.remove(position);
+        } else {
+            self.marked.push(id);
+        }
+    }
+    pub fn clear(&mut self) {
+        self.anchor = None;
+        self.marked.clear();
+    }
+    #[must_use]
+    pub fn contains(&self, position: usize, id: usize, cursor_position: usize) -> bool {
+        self.marked.contains(&id)
+            || self.anchor.is_some_and(|anchor| {
+                (anchor.min(cursor_position)..=anchor.max(cursor_position)).contains(&position)
+            })
+    }
+    /// The ids of all selected reminders, that still exist.
+    #[must_use]
+    pub fn ids(&self, reminders: &[Reminder], cursor_position: usize) -> Vec<usize> {
+        reminders
+            .iter()
+            .enumerate()
+            .filter(|(position, reminder)| self.contains(*position, reminder.id(), cursor_position))
+            .map(|(_, reminder)| reminder.id())
+            .collect()
+    }
+}
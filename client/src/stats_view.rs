@@ -0,0 +1,107 @@
+use std::io::{Stdout, Write};
+
+use colored::Colorize;
+use config::Config;
+use crossterm::{
+    cursor,
+    event::{read, Event, KeyCode},
+    execute, terminal,
+};
+use reminder::{
+    api_error::ApiError,
+    completion::{format_delay, CompletionStats},
+};
+
+use crate::key_reader::fetch;
+
+/// The ranges 'TAB' cycles through, `None` is the whole history.
+const STATS_RANGES: [Option<u32>; 3] = [Some(7), Some(30), None];
+
+/// The amount of reminders listed, most snoozed and most ignored first.
+const LISTED_REMINDERS: usize = 15;
+
+/// Shows the completion statistics until 'esc' or 'q' is pressed.
+pub fn show_stats(
+    stdout: &mut Stdout,
+    request_client: &reqwest::blocking::Client,
+    config: &Config,
+) -> Result<(), ApiError> {
+    let stats_url = format!(
+        "http://{}:{}/stats",
+        config.network().remote_ip(),
+        config.network().port()
+    );
+    let mut range: usize = 0;
+    loop {
+        let days = STATS_RANGES.get(range).copied().flatten();
+        let request = match days {
+            Some(days) => request_client.get(&stats_url).query(&[("days", days)]),
+            None => request_client.get(&stats_url),
+        };
+        let stats: CompletionStats = fetch(request)?;
+        let _trash_bin = execute!(
+            stdout,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        );
+        if stdout
+            .write_all(build_stats(&stats, days).as_bytes())
+            .is_err()
+        {
+            return Ok(());
+        }
+        let _trash_bin = stdout.flush();
+        let Ok(Event::Key(event)) = read() else {
+            continue;
+        };
+        #[allow(clippy::wildcard_enum_match_arm)]
+        match event.code {
+            KeyCode::Tab => {
+                range = range.saturating_add(1);
+                if range >= STATS_RANGES.len() {
+                    range = 0;
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'I') => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+fn build_stats(stats: &CompletionStats, days: Option<u32>) -> String {
+    let mut result = String::new();
+    result.push_str(
+        format!(
+            "{}\n\r",
+            format!(
+                "Stats of {}: 'TAB' -> change range | 'esc' -> back",
+                days.map_or_else(
+                    || String::from("everything"),
+                    |days| format!("the last {days} days")
+                )
+            )
+            .bright_yellow()
+        )
+        .as_str(),
+    );
+    if stats.days().is_empty() {
+        result.push_str(" Nothing fired yet\n\r");
+        return result;
+    }
+    result.push_str(
+        format!(
+            " average confirmation delay {}\n\r\n\r",
+            format_delay(stats.average_confirmation_delay())
+        )
+        .as_str(),
+    );
+    for day in stats.days().iter().rev().take(7).rev() {
+        result.push_str(format!(" {day}\n\r").as_str());
+    }
+    result.push_str("\n\r");
+    for reminder in stats.reminders().iter().take(LISTED_REMINDERS) {
+        result.push_str(format!(" {reminder}\n\r").as_str());
+    }
+    result
+}
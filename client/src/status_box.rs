@@ -1,14 +1,43 @@
-use reminder::{past_event::PastEvent, reminder::my_local_offset};
+use colored::Colorize;
+use reminder::{
+    api_error::ApiError, history::HistoryPreview, library::ReminderTemplate,
+    past_event::LoggedEvent,
+};
 
-#[allow(clippy::module_name_repetitions)]
-pub fn build_status_box(last_event: &PastEvent) -> String {
+/// The amount of events visible at once in the event history pane.
+const EVENT_PANE_HEIGHT: usize = 5;
+
+#[allow(clippy::module_name_repetitions, clippy::too_many_lines)]
+pub fn build_status_box(
+    events: &[LoggedEvent],
+    event_scroll: usize,
+    library: &[ReminderTemplate],
+    history: &HistoryPreview,
+    last_error: Option<&ApiError>,
+) -> String {
     let mut result = String::new();
     result.push_str("           =======================================\n\r");
     result.push_str(format!("           | {:<36}|\n\r", "'j', 'k' -> up, down").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'n' -> new").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'rn' -> rename").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'rt' -> retime").as_str());
-    result.push_str(format!("           | {:<36}|\n\r", "'s' -> snooze").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'s', 'S' -> snooze, snooze for").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'rz' -> snooze duration").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'rw' -> warn before due").as_str());
+    result.push_str(
+        format!(
+            "           | {:<36}|\n\r",
+            "'r#', 'f' -> tags, show only tag"
+        )
+        .as_str(),
+    );
+    result.push_str(
+        format!(
+            "           | {:<36}|\n\r",
+            "'rp', 'o' -> priority, sort order"
+        )
+        .as_str(),
+    );
     result.push_str(
         format!(
             "           | {:<36}|\n\r",
@@ -24,7 +53,27 @@ pub fn build_status_box(last_event: &PastEvent) -> String {
         .as_str(),
     );
     result.push_str(format!("           | {:<36}|\n\r", "'esc' -> unmark everything").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'v', 'V' -> select range, mark").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'x' -> checklist").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'P' -> pomodoro (' ' pauses)").as_str());
+    result.push_str(
+        format!(
+            "           | {:<36}|\n\r",
+            "'C', 'rc' -> new chain, start chain"
+        )
+        .as_str(),
+    );
+    result.push_str(
+        format!(
+            "           | {:<36}|\n\r",
+            "'rl' -> link, start next when done"
+        )
+        .as_str(),
+    );
+    result.push_str(format!("           | {:<36}|\n\r", "'A' -> archive").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'I' -> stats").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'e' -> repeat").as_str());
+    result.push_str(format!("           | {:<36}|\n\r", "'a', 'c' -> nag, acknowledge").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'+' -> add to endtime").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'-' -> subtract from endtime").as_str());
     result.push_str(
@@ -37,19 +86,22 @@ pub fn build_status_box(last_event: &PastEvent) -> String {
     result.push_str(
         format!(
             "           | {:<36}|\n\r",
-            "'s' -> toggle 'send external message'"
+            "'m' -> toggle 'send external message'"
         )
         .as_str(),
     );
+    result.push_str(format!("           | {:<36}|\n\r", "'L' -> save to library").as_str());
+    for (i, template) in library.iter().take(9).enumerate() {
+        result.push_str(
+            format!(
+                "           | {:<36.36}|\n\r",
+                format!("'{}' -> {}", i.saturating_add(1), template.name())
+            )
+            .as_str(),
+        );
+    }
     result.push_str(format!("           | {:<36}|\n\r", "'ENTER' -> Edit").as_str());
     result.push_str(format!("           | {:<36}|\n\r", "'CTRL' + 'c' -> exit(0)").as_str());
-    if my_local_offset().is_utc() {
-        for _ in 0..100 {
-            result.push_str(
-                format!("           | {:<36}|\n\r", "Local Offset NOT found!!!").as_str(),
-            );
-        }
-    }
     result.push_str(
         format!(
             "           | Version: {:<27}|\n\r",
@@ -57,9 +109,44 @@ pub fn build_status_box(last_event: &PastEvent) -> String {
         )
         .as_str(),
     );
-    result.push_str(format!("           | {:<36}|\n\r", "'u' -> undo").as_str());
-    result.push_str(format!("           | {:<36}|\n\r", "'U' -> redo").as_str());
+    result.push_str(
+        format!(
+            "           | {:<36.36}|\n\r",
+            format!("'u' -> undo {}", history.undo.as_deref().unwrap_or("-"))
+        )
+        .as_str(),
+    );
+    result.push_str(
+        format!(
+            "           | {:<36.36}|\n\r",
+            format!("'U' -> redo {}", history.redo.as_deref().unwrap_or("-"))
+        )
+        .as_str(),
+    );
+    result.push_str(
+        format!(
+            "           | {:<36}|\n\r",
+            "'K', 'J' -> scroll event history"
+        )
+        .as_str(),
+    );
     result.push_str("           =======================================\n\r");
-    result.push_str(format!("            {:^54}\n\n\r", last_event.to_string()).as_str());
+    let newest_visible_event = events
+        .len()
+        .saturating_sub(event_scroll.min(events.len().saturating_sub(EVENT_PANE_HEIGHT)));
+    let visible_events = events
+        .get(newest_visible_event.saturating_sub(EVENT_PANE_HEIGHT)..newest_visible_event)
+        .unwrap_or_default();
+    for _ in visible_events.len()..EVENT_PANE_HEIGHT {
+        result.push_str("\n\r");
+    }
+    for event in visible_events {
+        result.push_str(format!("            {event}\n\r").as_str());
+    }
+    if let Some(error) = last_error {
+        result.push_str(format!("           {}\n\r", format!("! {error}").bright_red()).as_str());
+    } else {
+        result.push_str("\n\r");
+    }
     result
 }
@@ -2,13 +2,30 @@ use std::{fs, net::Ipv4Addr, str::FromStr};
 
 use serde::Deserialize;
 
-use reminder::root_path;
+use reminder::{priority::SortOrder, root_path};
 
 const CONFIG_FILE_NAME: &str = "remindy.toml";
 
 #[derive(Deserialize, Default, Clone)]
 pub struct Config {
+    /// An IANA timezone like `Europe/Berlin`, the system timezone if not set.
+    timezone: Option<String>,
     network: Network,
+    #[serde(default)]
+    notifications: Notifications,
+    #[serde(default)]
+    auth: Auth,
+    #[serde(default)]
+    snooze: Snooze,
+    #[serde(default)]
+    retention: Retention,
+    #[serde(default)]
+    nag: Nag,
+    /// The order the clients list reminders in, `due` or `priority`.
+    #[serde(default)]
+    sort_order: SortOrder,
+    #[serde(default)]
+    pomodoro: Pomodoro,
 }
 
 impl Config {
@@ -33,9 +50,41 @@ impl Config {
         panic!("home_dir not found")
     }
     #[must_use]
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+    #[must_use]
     pub fn network(&self) -> &Network {
         &self.network
     }
+    #[must_use]
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+    #[must_use]
+    pub fn auth(&self) -> &Auth {
+        &self.auth
+    }
+    #[must_use]
+    pub fn snooze(&self) -> &Snooze {
+        &self.snooze
+    }
+    #[must_use]
+    pub fn retention(&self) -> &Retention {
+        &self.retention
+    }
+    #[must_use]
+    pub fn nag(&self) -> &Nag {
+        &self.nag
+    }
+    #[must_use]
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort_order
+    }
+    #[must_use]
+    pub fn pomodoro(&self) -> &Pomodoro {
+        &self.pomodoro
+    }
 }
 
 #[derive(Deserialize, Default, Clone)]
@@ -43,6 +92,10 @@ pub struct Network {
     remote_ip: Option<String>,
     local_ip: String,
     port: String,
+    /// Sent by the clients as `Authorization: Bearer <token>`.
+    token: Option<String>,
+    /// Identifies this client to the server, so it gets every alert delivered. Defaults to the hostname.
+    client_id: Option<String>,
 }
 impl Network {
     #[must_use]
@@ -73,4 +126,219 @@ impl Network {
     pub fn port_as_u16(&self) -> u16 {
         self.port.parse().unwrap()
     }
+    #[must_use]
+    pub fn token(&self) -> Option<&String> {
+        self.token.as_ref()
+    }
+    #[must_use]
+    pub fn client_id(&self) -> String {
+        self.client_id
+            .clone()
+            .or_else(hostname)
+            .unwrap_or_else(|| String::from("remindy"))
+    }
+}
+
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+}
+
+#[derive(Deserialize, Default, Clone)]
+pub struct Notifications {
+    #[serde(default)]
+    channels: Vec<NotificationChannel>,
+}
+impl Notifications {
+    #[must_use]
+    pub fn channels(&self) -> &[NotificationChannel] {
+        &self.channels
+    }
+}
+
+/// A single backend, that gets notified when a reminder with `send_e_message` is due.
+/// Configured as `[[notifications.channels]]` with a `type` key in `remindy.toml`.
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// POSTs the due reminder as json to `url`.
+    Webhook { url: String },
+    /// Sends a mail through `smtp_url` (e.g. `smtp://localhost:25` or `smtps://mail.example.com`).
+    Email {
+        smtp_url: String,
+        username: Option<String>,
+        password: Option<String>,
+        from: String,
+        to: String,
+    },
+    /// Publishes to a ntfy topic url (e.g. `https://ntfy.sh/my-topic`).
+    Ntfy { url: String, token: Option<String> },
+    /// Publishes to the `/message` endpoint of a gotify server.
+    Gotify { url: String, token: String },
+    /// Sends a whatsapp message through the twilio api, `api_url` defaults to `https://api.twilio.com`.
+    Twilio {
+        account_sid: String,
+        auth_token: String,
+        from: String,
+        to: String,
+        api_url: Option<String>,
+    },
+    /// Runs `command` through `sh -c`.
+    /// The reminder is exposed as `REMINDY_ID`, `REMINDY_NAME` and `REMINDY_DESCRIPTION`.
+    Command { command: String },
+}
+
+/// The users, that may access the server.
+/// Without any `[[auth.users]]` the server does not check tokens at all.
+#[derive(Deserialize, Default, Clone)]
+pub struct Auth {
+    #[serde(default)]
+    users: Vec<User>,
+    /// Gets every reminder, that was created before authentication was set up.
+    default_user: Option<String>,
+}
+impl Auth {
+    #[must_use]
+    pub fn users(&self) -> &[User] {
+        &self.users
+    }
+    /// The configured `default_user`, or else the first user. `None` without any users.
+    #[must_use]
+    pub fn default_user(&self) -> Option<&str> {
+        if self.users.is_empty() {
+            return None;
+        }
+        self.default_user
+            .as_deref()
+            .or_else(|| self.users.first().map(|user| user.name.as_str()))
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct User {
+    name: String,
+    token: String,
+}
+impl User {
+    #[must_use]
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    #[must_use]
+    pub fn token(&self) -> &String {
+        &self.token
+    }
+}
+
+/// How long `s` snoozes a reminder, that has no snooze duration of its own.
+#[derive(Deserialize, Clone)]
+pub struct Snooze {
+    minutes: u32,
+}
+impl Default for Snooze {
+    fn default() -> Self {
+        Self { minutes: 5 }
+    }
+}
+impl Snooze {
+    #[must_use]
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+}
+
+/// What happens to finished reminders, that do not repeat.
+/// Without `after_confirmation` or `after_hours` they are kept until deleted by hand.
+#[derive(Deserialize, Default, Clone)]
+pub struct Retention {
+    /// Cleans up right after the confirmation.
+    #[serde(default)]
+    after_confirmation: bool,
+    /// Cleans up this many hours after the reminder finished, whether it was confirmed or not.
+    after_hours: Option<u32>,
+    /// Moves the reminders into the archive instead of deleting them.
+    #[serde(default)]
+    archive: bool,
+}
+impl Retention {
+    #[must_use]
+    pub fn after_confirmation(&self) -> bool {
+        self.after_confirmation
+    }
+    #[must_use]
+    pub fn after_hours(&self) -> Option<u32> {
+        self.after_hours
+    }
+    #[must_use]
+    pub fn archive(&self) -> bool {
+        self.archive
+    }
+}
+
+/// How reminders with nagging enabled keep alerting, until they are confirmed by hand.
+#[derive(Deserialize, Clone)]
+pub struct Nag {
+    /// Minutes between two alerts.
+    minutes: u32,
+    /// Also notifies the external channels once, when a reminder alerted this many times in vain.
+    escalate_after: Option<u32>,
+}
+impl Default for Nag {
+    fn default() -> Self {
+        Self {
+            minutes: 5,
+            escalate_after: None,
+        }
+    }
+}
+impl Nag {
+    #[must_use]
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+    #[must_use]
+    pub fn escalate_after(&self) -> Option<u32> {
+        self.escalate_after
+    }
+}
+
+/// The phases of a pomodoro session. After `cycles` pomodoros the long break ends the session.
+#[derive(Deserialize, Clone)]
+pub struct Pomodoro {
+    work_minutes: u32,
+    short_break_minutes: u32,
+    long_break_minutes: u32,
+    cycles: u32,
+}
+impl Default for Pomodoro {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            cycles: 4,
+        }
+    }
+}
+impl Pomodoro {
+    #[must_use]
+    pub fn work_minutes(&self) -> u32 {
+        self.work_minutes
+    }
+    #[must_use]
+    pub fn short_break_minutes(&self) -> u32 {
+        self.short_break_minutes
+    }
+    #[must_use]
+    pub fn long_break_minutes(&self) -> u32 {
+        self.long_break_minutes
+    }
+    #[must_use]
+    pub fn cycles(&self) -> u32 {
+        self.cycles
+    }
 }
@@ -1,54 +1,69 @@
-use std::cmp::Ordering;
-
 use config::Config;
-use reminder::reminder::Reminder;
+use reminder::{priority::Priority, reminder::Reminder};
+
+/// The amount of reminders shown in the block.
+const SHOWN_REMINDERS: usize = 7;
 
-#[allow(clippy::expect_used)]
+/// Shows the next reminders, only the ones with the tag given as first argument or `BLOCK_INSTANCE` if set.
 fn main() {
     let config = Config::new();
     let mut reminders: Vec<Reminder> = vec![];
-    if let Ok(response) = reqwest::blocking::get(format!(
+    let mut request = reqwest::blocking::Client::new().get(format!(
         "http://{}:{}/reminders",
         config.network().remote_ip(),
         config.network().port()
-    )) {
+    ));
+    if let Some(tag) = std::env::args()
+        .nth(1)
+        .or_else(|| std::env::var("BLOCK_INSTANCE").ok())
+        .filter(|tag| !tag.trim().is_empty())
+    {
+        request = request.query(&[("tag", tag)]);
+    }
+    if let Some(token) = config.network().token() {
+        request = request.bearer_auth(token);
+    }
+    if let Ok(response) = request.send() {
         if let Ok(data) = response.json() {
             reminders = data;
         }
     }
-    reminders.sort_by(|a, b| {
-        if a.finish_time().cmp(&b.finish_time()) == Ordering::Less {
-            Ordering::Greater
-        } else {
-            Ordering::Less
-        }
-    });
+    config.sort_order().sort(&mut reminders);
     let reminders = reminders
         .iter()
         .filter(|reminder| reminder.remaining_duration().is_some());
 
     let reminders = reminders.rev();
-    let mut reminders = reminders.take(7);
-
-    print!(
-        "<span color='#50FF30'>{}</span>  <span color='#00BBBB'>{}</span>  <span color='#50FF30'>{}</span>  <span color='#00BBBB'>{}</span>  <span color='#50FF30'>{}</span>  <span color='#00BBBB'>{}</span>  <span color='#50FF30'>{}</span>",
+    let reminders = reminders.take(SHOWN_REMINDERS);
 
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders")),
-        format_reminder(reminders.next().expect("Could not read Reminders"))
-    );
+    let block: Vec<String> = reminders
+        .enumerate()
+        .map(|(i, reminder)| {
+            format!(
+                "<span color='{}'>{}</span>",
+                match reminder.priority() {
+                    Priority::Low => "#888888",
+                    Priority::High => "#FF5050",
+                    Priority::Normal if i % 2 == 0 => "#50FF30",
+                    Priority::Normal => "#00BBBB",
+                },
+                format_reminder(reminder)
+            )
+        })
+        .collect();
+    print!("{}", block.join("  "));
 }
 
 #[allow(clippy::arithmetic_side_effects)]
 fn format_reminder(reminder: &Reminder) -> String {
     if let Some(time_left) = reminder.remaining_duration() {
+        let name = match reminder.pomodoro() {
+            Some(pomodoro) => format!("{} {pomodoro}", reminder.name()),
+            None => reminder.name().to_string(),
+        };
         format!(
             "{} {:0>2}{}{:0>2}{}{:0>2}",
-            reminder.name(),
+            name,
             (time_left.whole_hours() - time_left.whole_days() * 24).to_string(),
             ":",
             (time_left.whole_minutes() - time_left.whole_hours() * 60).to_string(),
@@ -0,0 +1,81 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// What went wrong, so clients can react to an error without parsing its message.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCode {
+    ReminderNotFound,
+    TemplateNotFound,
+    ChecklistItemNotFound,
+    InvalidInput,
+    NothingToUndo,
+    NothingToRedo,
+    Unauthorized,
+    ServerUnreachable,
+    Internal,
+}
+
+/// The json body of every failed request to the server.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiError {
+    code: ApiErrorCode,
+    message: String,
+    reminder_id: Option<usize>,
+}
+
+impl ApiError {
+    #[must_use]
+    pub fn new(code: ApiErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            reminder_id: None,
+        }
+    }
+    #[must_use]
+    pub fn reminder_not_found(id: usize) -> Self {
+        Self::new(
+            ApiErrorCode::ReminderNotFound,
+            format!("There is no reminder with id {id}"),
+        )
+        .with_reminder_id(id)
+    }
+    #[must_use]
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorCode::InvalidInput, message)
+    }
+    #[must_use]
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorCode::Internal, message)
+    }
+    #[must_use]
+    pub fn with_reminder_id(mut self, id: usize) -> Self {
+        self.reminder_id = Some(id);
+        self
+    }
+    #[must_use]
+    pub fn code(&self) -> ApiErrorCode {
+        self.code
+    }
+    #[must_use]
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+    #[must_use]
+    pub fn reminder_id(&self) -> Option<usize> {
+        self.reminder_id
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(id) = self.reminder_id {
+            write!(f, "{} (reminder {id})", self.message)
+        } else {
+            write!(f, "{}", self.message)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use time::{format_description, OffsetDateTime};
+
+use crate::{
+    reminder::{my_local_offset, Reminder},
+    timezone::to_local,
+};
+
+/// A finished reminder, that the retention policy moved out of the reminder list into the `REMINDER_ARCHIVE_FILE`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArchivedReminder {
+    reminder: Reminder,
+    archived_at: OffsetDateTime,
+}
+
+impl ArchivedReminder {
+    #[must_use]
+    pub fn new(reminder: Reminder) -> Self {
+        Self {
+            reminder,
+            archived_at: OffsetDateTime::now_utc().to_offset(my_local_offset()),
+        }
+    }
+    #[must_use]
+    pub fn reminder(&self) -> &Reminder {
+        &self.reminder
+    }
+    pub fn reminder_mut(&mut self) -> &mut Reminder {
+        &mut self.reminder
+    }
+    #[must_use]
+    pub fn into_reminder(self) -> Reminder {
+        self.reminder
+    }
+    #[must_use]
+    pub fn archived_at(&self) -> OffsetDateTime {
+        self.archived_at
+    }
+}
+
+impl Display for ArchivedReminder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Ok(format) = format_description::parse("[day].[month].[year] [hour]:[minute]") else {
+            return Err(std::fmt::Error);
+        };
+        let Ok(finish_time) = to_local(self.reminder.finish_time()).format(&format) else {
+            return Err(std::fmt::Error);
+        };
+        let Ok(archived_at) = to_local(self.archived_at).format(&format) else {
+            return Err(std::fmt::Error);
+        };
+        write!(
+            f,
+            "{} {} {}",
+            self.reminder.name().trim_end().green(),
+            format!("ended {finish_time}").bright_red(),
+            format!("archived {archived_at}").bright_black()
+        )
+    }
+}
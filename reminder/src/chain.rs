@@ -0,0 +1,174 @@
+use crate::{api_error::ApiError, reminder::Reminder};
+
+/// The ids of the chain starting at `head`, in the order they run.
+/// Stops at a missing reminder or once the chain loops back onto itself.
+#[must_use]
+pub fn chain_ids(reminders: &[Reminder], head: usize) -> Vec<usize> {
+    let mut ids = vec![];
+    let mut next = Some(head);
+    while let Some(id) = next {
+        if ids.contains(&id) {
+            break;
+        }
+        let Some(reminder) = reminders.iter().find(|reminder| reminder.id() == id) else {
+            break;
+        };
+        ids.push(id);
+        next = reminder.next_in_chain();
+    }
+    ids
+}
+
+/// The reminder, that starts the reminder with `id` once it ended.
+#[must_use]
+pub fn previous_in_chain(reminders: &[Reminder], id: usize) -> Option<&Reminder> {
+    reminders
+        .iter()
+        .find(|reminder| reminder.next_in_chain() == Some(id))
+}
+
+/// Rejects linking the reminder with `id` to `next_id`, if that would loop back onto itself
+/// or give `next_id` a second reminder to start it, as every chain is a single line.
+#[allow(clippy::missing_errors_doc)]
+pub fn check_link(reminders: &[Reminder], id: usize, next_id: usize) -> Result<(), ApiError> {
+    if chain_ids(reminders, next_id).contains(&id) {
+        return Err(ApiError::invalid_input(
+            "A reminder can not start itself, the chain would never end",
+        )
+        .with_reminder_id(id));
+    }
+    if let Some(previous) =
+        previous_in_chain(reminders, next_id).filter(|previous| previous.id() != id)
+    {
+        return Err(ApiError::invalid_input(format!(
+            "'{}' already starts '{}' once it ends",
+            previous.name().trim_end(),
+            reminders
+                .iter()
+                .find(|reminder| reminder.id() == next_id)
+                .map_or("", |next| next.name().trim_end())
+        ))
+        .with_reminder_id(id));
+    }
+    Ok(())
+}
+
+/// Takes the reminder with `id` out of its chain, before it gets removed.
+/// The reminders, that would start it, end their chain there and the reminder waiting for it starts right away.
+pub fn unlink(reminders: &mut [Reminder], id: usize) {
+    let next_id = reminders
+        .iter()
+        .find(|reminder| reminder.id() == id)
+        .and_then(Reminder::next_in_chain);
+    for reminder in reminders.iter_mut() {
+        if reminder.id() == id || reminder.next_in_chain() == Some(id) {
+            reminder.set_next_in_chain(None);
+        } else if Some(reminder.id()) == next_id && reminder.waiting() {
+            reminder.restart();
+        }
+    }
+}
+
+/// The reminder with `id` and every reminder, that `unlink` changes along with it.
+#[must_use]
+pub fn linked_ids(reminders: &[Reminder], id: usize) -> Vec<usize> {
+    let mut ids = vec![id];
+    for reminder in reminders {
+        if reminder.next_in_chain() == Some(id) {
+            ids.push(reminder.id());
+        }
+        if reminder.id() == id {
+            ids.extend(reminder.next_in_chain());
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chain_ids, check_link, linked_ids, previous_in_chain, unlink};
+    use crate::{
+        api_error::ApiError,
+        reminder::Reminder,
+        test_reminders::{names, reminder},
+    };
+
+    /// `preheat -> bake -> rest`, with `bake` and `rest` waiting for their turn.
+    fn chain() -> Result<Vec<Reminder>, ApiError> {
+        let mut reminders = vec![
+            reminder(1, "preheat")?,
+            reminder(2, "bake")?,
+            reminder(3, "rest")?,
+        ];
+        for (reminder, next_id) in reminders.iter_mut().zip([Some(2), Some(3), None]) {
+            reminder.set_next_in_chain(next_id);
+            if reminder.id() > 1 {
+                reminder.wait_in_chain();
+            }
+        }
+        Ok(reminders)
+    }
+
+    #[test]
+    fn chain_ids_stop_at_loops() -> Result<(), ApiError> {
+        let mut reminders = chain()?;
+        assert_eq!(chain_ids(&reminders, 1), vec![1, 2, 3]);
+        assert_eq!(chain_ids(&reminders, 2), vec![2, 3]);
+        if let Some(rest) = reminders.get_mut(2) {
+            rest.set_next_in_chain(Some(1));
+        }
+        assert_eq!(chain_ids(&reminders, 2), vec![2, 3, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn unlinking_a_member_ends_the_chain_before_it() -> Result<(), ApiError> {
+        let mut reminders = chain()?;
+        assert_eq!(linked_ids(&reminders, 2), vec![2, 1, 3]);
+        unlink(&mut reminders, 2);
+        reminders.retain(|reminder| reminder.id() != 2);
+        assert_eq!(chain_ids(&reminders, 1), vec![1]);
+        assert_eq!(
+            reminders
+                .iter()
+                .map(Reminder::waiting)
+                .collect::<Vec<bool>>(),
+            vec![false, false]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn every_reminder_is_started_by_one_reminder_at_most() -> Result<(), ApiError> {
+        let mut reminders = chain()?;
+        reminders.push(reminder(4, "serve")?);
+        assert!(check_link(&reminders, 4, 2).is_err());
+        assert!(check_link(&reminders, 4, 3).is_err());
+        assert!(check_link(&reminders, 4, 1).is_ok());
+        // Linking again to the reminder it already starts changes nothing
+        assert!(check_link(&reminders, 1, 2).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn links_that_close_a_loop_are_rejected() -> Result<(), ApiError> {
+        let reminders = chain()?;
+        assert!(check_link(&reminders, 3, 1).is_err());
+        assert!(check_link(&reminders, 2, 1).is_err());
+        assert!(check_link(&reminders, 2, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn a_new_reminder_with_a_reused_id_is_not_part_of_the_chain() -> Result<(), ApiError> {
+        let mut reminders = chain()?;
+        unlink(&mut reminders, 3);
+        reminders.retain(|reminder| reminder.id() != 3);
+        // Older databases handed out the highest id plus one, so the id of `rest` could come back.
+        reminders.push(reminder(3, "laundry")?);
+        assert_eq!(chain_ids(&reminders, 1), vec![1, 2]);
+        assert!(previous_in_chain(&reminders, 3).is_none());
+        assert_eq!(names(&reminders), vec!["preheat", "bake", "laundry"]);
+        Ok(())
+    }
+}
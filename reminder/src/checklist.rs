@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// One step of a reminder, like `tag the release` or `write the changelog`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChecklistItem {
+    text: String,
+    done: bool,
+}
+
+impl ChecklistItem {
+    #[must_use]
+    pub fn new(text: String) -> Self {
+        Self { text, done: false }
+    }
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    #[must_use]
+    pub fn done(&self) -> bool {
+        self.done
+    }
+    pub fn toggle(&mut self) {
+        self.done = !self.done;
+    }
+    pub fn uncheck(&mut self) {
+        self.done = false;
+    }
+}
+
+impl Display for ChecklistItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", if self.done { "☑" } else { "☐" }, self.text)
+    }
+}
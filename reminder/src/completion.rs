@@ -0,0 +1,538 @@
+use std::fmt::Display;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use time::{format_description, Date, Duration, OffsetDateTime};
+
+use crate::{
+    reminder::{my_local_offset, Reminder},
+    timezone::to_local,
+};
+
+/// The amount of completions kept in the `CompletionLog`, older ones get dropped.
+pub const COMPLETION_LOG_LENGTH: usize = 5000;
+
+/// One occurrence of a reminder, from the moment it fired until it was confirmed.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Completion {
+    reminder_id: usize,
+    name: String,
+    owner: Option<String>,
+    repeating: bool,
+    due_time: OffsetDateTime,
+    confirmed_at: Option<OffsetDateTime>,
+    snooze_count: usize,
+    pause_count: usize,
+}
+
+impl Completion {
+    #[must_use]
+    pub fn reminder_id(&self) -> usize {
+        self.reminder_id
+    }
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+    #[must_use]
+    pub fn repeating(&self) -> bool {
+        self.repeating
+    }
+    #[must_use]
+    pub fn due_time(&self) -> OffsetDateTime {
+        self.due_time
+    }
+    #[must_use]
+    pub fn confirmed_at(&self) -> Option<OffsetDateTime> {
+        self.confirmed_at
+    }
+    #[must_use]
+    pub fn snooze_count(&self) -> usize {
+        self.snooze_count
+    }
+    #[must_use]
+    pub fn pause_count(&self) -> usize {
+        self.pause_count
+    }
+    /// How long it took from the due time to the confirmation, snoozes included.
+    #[must_use]
+    pub fn confirmation_delay(&self) -> Option<Duration> {
+        #[allow(clippy::arithmetic_side_effects)]
+        self.confirmed_at
+            .map(|confirmed_at| confirmed_at - self.due_time)
+    }
+}
+
+/// A bounded, persisted history of when reminders fired and when they were confirmed.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct CompletionLog {
+    completions: Vec<Completion>,
+}
+
+impl CompletionLog {
+    /// Records that `reminder` fired.
+    /// A snoozed reminder firing again continues its open completion instead of starting a new one.
+    pub fn fired(&mut self, reminder: &Reminder) {
+        if reminder.snooze_count() > 0 {
+            if let Some(completion) = self.open_completion(reminder.id()) {
+                completion.snooze_count = reminder.snooze_count();
+                completion.pause_count = reminder.pause_count();
+                return;
+            }
+        }
+        self.completions.push(Completion {
+            reminder_id: reminder.id(),
+            name: reminder.name().trim_end().to_string(),
+            owner: reminder.owner().map(str::to_string),
+            repeating: reminder.repeating(),
+            due_time: reminder.finish_time(),
+            confirmed_at: None,
+            snooze_count: reminder.snooze_count(),
+            pause_count: reminder.pause_count(),
+        });
+        if self.completions.len() > COMPLETION_LOG_LENGTH {
+            self.completions.remove(0);
+        }
+    }
+    /// Closes the open completion of `reminder`.
+    pub fn confirmed(&mut self, reminder: &Reminder) {
+        if let Some(completion) = self.open_completion(reminder.id()) {
+            completion.confirmed_at = Some(OffsetDateTime::now_utc().to_offset(my_local_offset()));
+            completion.snooze_count = reminder.snooze_count();
+            completion.pause_count = reminder.pause_count();
+        }
+    }
+    /// Keeps the snooze and pause counts of the open completion of `reminder` up to date.
+    pub fn update(&mut self, reminder: &Reminder) {
+        if let Some(completion) = self.open_completion(reminder.id()) {
+            completion.snooze_count = reminder.snooze_count();
+            completion.pause_count = reminder.pause_count();
+        }
+    }
+    #[must_use]
+    pub fn completions(&self) -> &[Completion] {
+        &self.completions
+    }
+    /// The newest completion of the reminder with `id`, if it is not confirmed yet.
+    fn open_completion(&mut self, id: usize) -> Option<&mut Completion> {
+        self.completions
+            .iter_mut()
+            .rev()
+            .find(|completion| completion.reminder_id == id)
+            .filter(|completion| completion.confirmed_at.is_none())
+    }
+}
+
+/// What the completion history says about a set of reminders, as answered by `/stats`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompletionStats {
+    /// Oldest day first.
+    days: Vec<DayStats>,
+    average_confirmation_delay: Option<Duration>,
+    /// The most snoozed reminders first, then the ones left unconfirmed most often.
+    reminders: Vec<ReminderStats>,
+}
+
+impl CompletionStats {
+    #[must_use]
+    pub fn new<'a>(completions: impl Iterator<Item = &'a Completion>) -> Self {
+        let mut days: Vec<DayStats> = vec![];
+        let mut reminders: Vec<ReminderStats> = vec![];
+        let mut delays = vec![];
+        for completion in completions {
+            let date = to_local(completion.due_time).date();
+            if !days.iter().any(|day| day.date == date) {
+                days.push(DayStats {
+                    date,
+                    fired: 0,
+                    confirmed: 0,
+                });
+            }
+            if let Some(day) = days.iter_mut().find(|day| day.date == date) {
+                day.fired = day.fired.saturating_add(1);
+                if completion.confirmed_at.is_some() {
+                    day.confirmed = day.confirmed.saturating_add(1);
+                }
+            }
+
+            if !reminders
+                .iter()
+                .any(|reminder| reminder.reminder_id == completion.reminder_id)
+            {
+                reminders.push(ReminderStats {
+                    reminder_id: completion.reminder_id,
+                    name: completion.name.clone(),
+                    repeating: completion.repeating,
+                    fired: 0,
+                    confirmed: 0,
+                    snoozes: 0,
+                    pauses: 0,
+                    average_confirmation_delay: None,
+                    delays: vec![],
+                });
+            }
+            let Some(reminder) = reminders
+                .iter_mut()
+                .find(|reminder| reminder.reminder_id == completion.reminder_id)
+            else {
+                continue;
+            };
+            reminder.name.clone_from(&completion.name);
+            reminder.repeating = completion.repeating;
+            reminder.fired = reminder.fired.saturating_add(1);
+            reminder.snoozes = reminder.snoozes.saturating_add(completion.snooze_count);
+            reminder.pauses = reminder.pauses.saturating_add(completion.pause_count);
+            if let Some(delay) = completion.confirmation_delay() {
+                reminder.confirmed = reminder.confirmed.saturating_add(1);
+                reminder.delays.push(delay);
+                delays.push(delay);
+            }
+        }
+        days.sort_by_key(|day| day.date);
+        for reminder in &mut reminders {
+            reminder.average_confirmation_delay = average(&reminder.delays);
+        }
+        reminders.sort_by(|a, b| {
+            b.snoozes
+                .cmp(&a.snoozes)
+                .then(b.unconfirmed().cmp(&a.unconfirmed()))
+        });
+        Self {
+            days,
+            average_confirmation_delay: average(&delays),
+            reminders,
+        }
+    }
+    #[must_use]
+    pub fn days(&self) -> &[DayStats] {
+        &self.days
+    }
+    #[must_use]
+    pub fn average_confirmation_delay(&self) -> Option<Duration> {
+        self.average_confirmation_delay
+    }
+    #[must_use]
+    pub fn reminders(&self) -> &[ReminderStats] {
+        &self.reminders
+    }
+}
+
+fn average(durations: &[Duration]) -> Option<Duration> {
+    let count = i32::try_from(durations.len()).ok()?;
+    durations
+        .iter()
+        .fold(Duration::ZERO, |sum, duration| {
+            sum.saturating_add(*duration)
+        })
+        .checked_div(count)
+}
+
+/// How many reminders fired and got confirmed on one day.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DayStats {
+    date: Date,
+    fired: usize,
+    confirmed: usize,
+}
+
+impl DayStats {
+    #[must_use]
+    pub fn date(&self) -> Date {
+        self.date
+    }
+    #[must_use]
+    pub fn fired(&self) -> usize {
+        self.fired
+    }
+    #[must_use]
+    pub fn confirmed(&self) -> usize {
+        self.confirmed
+    }
+}
+
+impl Display for DayStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Ok(date_format) = format_description::parse("[weekday repr:short] [day].[month].")
+        else {
+            return Err(std::fmt::Error);
+        };
+        let Ok(date) = self.date.format(&date_format) else {
+            return Err(std::fmt::Error);
+        };
+        write!(
+            f,
+            "{} {:>3} fired {:>3} confirmed",
+            date.bright_black(),
+            self.fired,
+            self.confirmed
+        )
+    }
+}
+
+/// How one reminder fared over all of its completions.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReminderStats {
+    reminder_id: usize,
+    name: String,
+    repeating: bool,
+    fired: usize,
+    confirmed: usize,
+    snoozes: usize,
+    pauses: usize,
+    average_confirmation_delay: Option<Duration>,
+    #[serde(skip)]
+    delays: Vec<Duration>,
+}
+
+impl ReminderStats {
+    #[must_use]
+    pub fn reminder_id(&self) -> usize {
+        self.reminder_id
+    }
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    #[must_use]
+    pub fn repeating(&self) -> bool {
+        self.repeating
+    }
+    #[must_use]
+    pub fn fired(&self) -> usize {
+        self.fired
+    }
+    #[must_use]
+    pub fn confirmed(&self) -> usize {
+        self.confirmed
+    }
+    /// Occurrences that fired but were never confirmed.
+    #[must_use]
+    pub fn unconfirmed(&self) -> usize {
+        self.fired.saturating_sub(self.confirmed)
+    }
+    #[must_use]
+    pub fn snoozes(&self) -> usize {
+        self.snoozes
+    }
+    #[must_use]
+    pub fn pauses(&self) -> usize {
+        self.pauses
+    }
+    #[must_use]
+    pub fn average_confirmation_delay(&self) -> Option<Duration> {
+        self.average_confirmation_delay
+    }
+}
+
+impl Display for ReminderStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unconfirmed = format!("{:>3} ignored", self.unconfirmed());
+        write!(
+            f,
+            "{:<20}{} {:>3} fired {:>3} snoozed {:>3} paused {} avg {}",
+            self.name.as_str().green(),
+            if self.repeating { "∞" } else { " " },
+            self.fired,
+            self.snoozes,
+            self.pauses,
+            if self.repeating && self.unconfirmed() > self.confirmed {
+                unconfirmed.bright_red()
+            } else {
+                unconfirmed.normal()
+            },
+            format_delay(self.average_confirmation_delay)
+        )
+    }
+}
+
+/// Formats a confirmation delay as `1d 02:03`, `02:03:04` or `-` if there is none.
+#[must_use]
+pub fn format_delay(delay: Option<Duration>) -> String {
+    let Some(delay) = delay else {
+        return String::from("-");
+    };
+    let delay = delay.max(Duration::ZERO);
+    #[allow(clippy::arithmetic_side_effects)]
+    if delay.whole_days() > 0 {
+        format!(
+            "{}d {:0>2}:{:0>2}",
+            delay.whole_days(),
+            delay.whole_hours() - delay.whole_days() * 24,
+            delay.whole_minutes() - delay.whole_hours() * 60
+        )
+    } else {
+        format!(
+            "{:0>2}:{:0>2}:{:0>2}",
+            delay.whole_hours(),
+            delay.whole_minutes() - delay.whole_hours() * 60,
+            delay.whole_seconds() - delay.whole_minutes() * 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{
+        macros::{date, offset},
+        Duration, OffsetDateTime, Time,
+    };
+
+    use super::{format_delay, Completion, CompletionLog, CompletionStats, COMPLETION_LOG_LENGTH};
+    use crate::{api_error::ApiError, test_reminders::reminder, timezone::at_local_time};
+
+    fn completion(
+        reminder_id: usize,
+        due_time: OffsetDateTime,
+        delay: Option<Duration>,
+        snooze_count: usize,
+    ) -> Completion {
+        Completion {
+            reminder_id,
+            name: format!("reminder {reminder_id}"),
+            owner: None,
+            repeating: false,
+            due_time,
+            confirmed_at: delay.map(|delay| due_time.saturating_add(delay)),
+            snooze_count,
+            pause_count: 0,
+        }
+    }
+
+    /// The local midnight, that starts the 10th of March 2024, a 23 hour day in US timezones.
+    fn midnight() -> OffsetDateTime {
+        at_local_time(date!(2024 - 03 - 10), Time::MIDNIGHT)
+    }
+
+    #[test]
+    fn an_empty_history_has_no_stats() {
+        let stats = CompletionStats::new([].iter());
+        assert!(stats.days().is_empty());
+        assert!(stats.reminders().is_empty());
+        assert_eq!(stats.average_confirmation_delay(), None);
+    }
+
+    #[test]
+    fn days_are_split_at_local_midnight() {
+        let completions = [
+            completion(1, midnight().saturating_add(Duration::minutes(1)), None, 0),
+            completion(1, midnight().saturating_sub(Duration::minutes(1)), None, 0),
+            completion(
+                2,
+                midnight().saturating_add(Duration::hours(22)),
+                Some(Duration::minutes(5)),
+                0,
+            ),
+        ];
+        let stats = CompletionStats::new(completions.iter());
+        let days: Vec<_> = stats
+            .days()
+            .iter()
+            .map(|day| (day.date(), day.fired(), day.confirmed()))
+            .collect();
+        assert_eq!(
+            days,
+            vec![(date!(2024 - 03 - 09), 1, 0), (date!(2024 - 03 - 10), 2, 1)]
+        );
+    }
+
+    #[test]
+    fn days_follow_the_local_timezone_not_the_stored_offset() {
+        // A minute before local midnight is already the next day in UTC+14
+        let due_time = midnight()
+            .saturating_sub(Duration::minutes(1))
+            .to_offset(offset!(+14));
+        let stats = CompletionStats::new([completion(1, due_time, None, 0)].iter());
+        let dates: Vec<_> = stats.days().iter().map(super::DayStats::date).collect();
+        assert_eq!(dates, vec![date!(2024 - 03 - 09)]);
+    }
+
+    #[test]
+    fn reminders_are_ranked_by_snoozes_then_ignored_occurrences() {
+        let due_time = midnight();
+        let completions = [
+            completion(1, due_time, Some(Duration::minutes(20)), 0),
+            completion(1, due_time, None, 0),
+            completion(1, due_time, None, 0),
+            completion(2, due_time, None, 0),
+            completion(3, due_time, Some(Duration::minutes(10)), 3),
+        ];
+        let stats = CompletionStats::new(completions.iter());
+        let ranking: Vec<_> = stats
+            .reminders()
+            .iter()
+            .map(|reminder| {
+                (
+                    reminder.reminder_id(),
+                    reminder.snoozes(),
+                    reminder.unconfirmed(),
+                )
+            })
+            .collect();
+        assert_eq!(ranking, vec![(3, 3, 0), (1, 0, 2), (2, 0, 1)]);
+        assert_eq!(
+            stats.average_confirmation_delay(),
+            Some(Duration::minutes(15))
+        );
+        assert_eq!(
+            stats
+                .reminders()
+                .get(1)
+                .and_then(super::ReminderStats::average_confirmation_delay),
+            Some(Duration::minutes(20))
+        );
+    }
+
+    #[test]
+    fn snoozed_reminders_continue_their_open_completion() -> Result<(), ApiError> {
+        let mut log = CompletionLog::default();
+        let mut reminder = reminder(1, "snoozed")?;
+        log.fired(&reminder);
+        reminder.snooze(Duration::minutes(5));
+        log.fired(&reminder);
+        assert_eq!(log.completions().len(), 1);
+        assert_eq!(
+            log.completions().first().map(Completion::snooze_count),
+            Some(1)
+        );
+        log.confirmed(&reminder);
+        log.fired(&reminder);
+        assert_eq!(log.completions().len(), 2);
+        assert!(log
+            .completions()
+            .first()
+            .is_some_and(|completion| completion.confirmed_at().is_some()));
+        Ok(())
+    }
+
+    #[test]
+    fn the_log_drops_the_oldest_completions() -> Result<(), ApiError> {
+        let mut log = CompletionLog::default();
+        for id in 0..=COMPLETION_LOG_LENGTH {
+            log.fired(&reminder(id, "many")?);
+        }
+        assert_eq!(log.completions().len(), COMPLETION_LOG_LENGTH);
+        assert_eq!(
+            log.completions().first().map(Completion::reminder_id),
+            Some(1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delays_are_formatted_by_their_size() {
+        assert_eq!(format_delay(None), "-");
+        assert_eq!(
+            format_delay(Some(Duration::seconds(2 * 3600 + 3 * 60 + 4))),
+            "02:03:04"
+        );
+        assert_eq!(
+            format_delay(Some(Duration::minutes(26 * 60 + 3))),
+            "1d 02:03"
+        );
+        assert_eq!(format_delay(Some(Duration::minutes(-5))), "00:00:00");
+    }
+}
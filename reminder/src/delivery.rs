@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::reminder::my_local_offset;
+
+/// A client, that registered itself with the server to get every alert delivered.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RegisteredClient {
+    id: String,
+    /// The user the client authenticated as, `None` without authentication.
+    owner: Option<String>,
+    last_seen: OffsetDateTime,
+}
+
+impl RegisteredClient {
+    #[must_use]
+    pub fn new(id: String, owner: Option<String>) -> Self {
+        Self {
+            id,
+            owner,
+            last_seen: OffsetDateTime::now_utc().to_offset(my_local_offset()),
+        }
+    }
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+    #[must_use]
+    pub fn last_seen(&self) -> OffsetDateTime {
+        self.last_seen
+    }
+    pub fn seen(&mut self) {
+        self.last_seen = OffsetDateTime::now_utc().to_offset(my_local_offset());
+    }
+}
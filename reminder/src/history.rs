@@ -0,0 +1,382 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::reminder::Reminder;
+
+/// The amount of operations kept for undo and for redo, older ones get dropped.
+pub const HISTORY_LENGTH: usize = 50;
+
+/// The fields of a single reminder, that an operation changed, with their values before and after it.
+/// `before` is `None` for created reminders and `after` is `None` for deleted ones.
+#[derive(Clone, Serialize, Deserialize)]
+struct ReminderChange {
+    id: usize,
+    before: Option<Map<String, Value>>,
+    after: Option<Map<String, Value>>,
+}
+
+impl ReminderChange {
+    fn between(id: usize, before: Option<&Reminder>, after: Option<&Reminder>) -> Option<Self> {
+        let before = before.map(fields);
+        let after = after.map(fields);
+        let (before, after) = match (before, after) {
+            (Some(mut before), Some(mut after)) => {
+                after.retain(|key, value| before.get(key) != Some(value));
+                before.retain(|key, _| after.contains_key(key));
+                if after.is_empty() {
+                    return None;
+                }
+                (Some(before), Some(after))
+            }
+            (None, None) => return None,
+            fields => fields,
+        };
+        Some(Self { id, before, after })
+    }
+    /// Overwrites only the recorded fields, so changes to other fields made in the meantime survive.
+    /// `source` is the opposite side of `target`, it tells apart a changed reminder from a created one.
+    fn set_fields(
+        &self,
+        reminders: &mut Vec<Reminder>,
+        source: Option<&Map<String, Value>>,
+        target: Option<&Map<String, Value>>,
+    ) {
+        let position = reminders
+            .iter()
+            .position(|reminder| reminder.id() == self.id);
+        match (source, target, position) {
+            (_, None, Some(position)) => {
+                reminders.remove(position);
+            }
+            (Some(_), Some(target), Some(position)) => {
+                let Some(reminder) = reminders.get_mut(position) else {
+                    return;
+                };
+                let mut current = fields(reminder);
+                current.extend(target.clone());
+                if let Ok(changed_reminder) = serde_json::from_value(Value::Object(current)) {
+                    *reminder = changed_reminder;
+                }
+            }
+            (None, Some(target), None) => {
+                if let Ok(reminder) = serde_json::from_value(Value::Object(target.clone())) {
+                    reminders.push(reminder);
+                }
+            }
+            // The reminder was deleted in the meantime, or its id got reused by a new one
+            _ => (),
+        }
+    }
+}
+
+fn fields(reminder: &Reminder) -> Map<String, Value> {
+    match serde_json::to_value(reminder) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+/// The reminders a request is about to change, taken under the same lock as the change itself,
+/// so nothing changed by the tick thread or by other requests ends up in its operation.
+pub struct Recording {
+    ids: Vec<usize>,
+    before: Vec<Reminder>,
+}
+
+impl Recording {
+    /// Remembers the reminders with `ids`, ids without a reminder yet are recorded as created once they exist.
+    #[must_use]
+    pub fn start(reminders: &[Reminder], ids: &[usize]) -> Self {
+        Self {
+            ids: ids.to_vec(),
+            before: reminders_with_ids(reminders, ids),
+        }
+    }
+    /// What changed on the remembered reminders since `start`, `None` if nothing did.
+    #[must_use]
+    pub fn finish(
+        self,
+        action: &str,
+        owner: Option<String>,
+        reminders: &[Reminder],
+    ) -> Option<Operation> {
+        Operation::record(
+            action,
+            owner,
+            &self.before,
+            &reminders_with_ids(reminders, &self.ids),
+        )
+    }
+}
+
+fn reminders_with_ids(reminders: &[Reminder], ids: &[usize]) -> Vec<Reminder> {
+    reminders
+        .iter()
+        .filter(|reminder| ids.contains(&reminder.id()))
+        .cloned()
+        .collect()
+}
+
+/// Everything a single request changed, stored as the fields it touched, so it can be reverted on its own.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Operation {
+    label: String,
+    owner: Option<String>,
+    changes: Vec<ReminderChange>,
+}
+
+impl Operation {
+    /// Records the difference between `before` and `after`, `None` if nothing changed.
+    #[must_use]
+    fn record(
+        action: &str,
+        owner: Option<String>,
+        before: &[Reminder],
+        after: &[Reminder],
+    ) -> Option<Self> {
+        let mut ids: Vec<usize> = before.iter().chain(after).map(Reminder::id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let mut names: Vec<&str> = vec![];
+        let mut changes = vec![];
+        for id in ids {
+            let reminder_before = before.iter().find(|reminder| reminder.id() == id);
+            let reminder_after = after.iter().find(|reminder| reminder.id() == id);
+            if let Some(change) = ReminderChange::between(id, reminder_before, reminder_after) {
+                if let Some(reminder) = reminder_after.or(reminder_before) {
+                    names.push(reminder.name().trim_end());
+                }
+                changes.push(change);
+            }
+        }
+        if changes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            label: format!("{action} ({})", names.join(", ")),
+            owner,
+            changes,
+        })
+    }
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.label.as_str()
+    }
+    #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+    fn revert(&self, reminders: &mut Vec<Reminder>) {
+        for change in self.changes.iter().rev() {
+            change.set_fields(reminders, change.after.as_ref(), change.before.as_ref());
+        }
+    }
+    fn reapply(&self, reminders: &mut Vec<Reminder>) {
+        for change in &self.changes {
+            change.set_fields(reminders, change.before.as_ref(), change.after.as_ref());
+        }
+    }
+}
+
+/// The undo and redo stacks of all users.
+/// Every user only ever undoes and redoes their own operations.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct History {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+}
+
+impl History {
+    /// Adds a new operation, which makes everything its owner undid so far unredoable.
+    pub fn push(&mut self, operation: Operation) {
+        self.redo.retain(|redo| redo.owner != operation.owner);
+        self.undo.push(operation);
+        if self.undo.len() > HISTORY_LENGTH {
+            self.undo.remove(0);
+        }
+    }
+    /// Reverts the newest operation of `owner` and returns its label.
+    pub fn undo(&mut self, owner: Option<&str>, reminders: &mut Vec<Reminder>) -> Option<String> {
+        let position = self
+            .undo
+            .iter()
+            .rposition(|operation| operation.owner() == owner)?;
+        let operation = self.undo.remove(position);
+        operation.revert(reminders);
+        let label = operation.label.clone();
+        self.redo.push(operation);
+        if self.redo.len() > HISTORY_LENGTH {
+            self.redo.remove(0);
+        }
+        Some(label)
+    }
+    /// Reapplies the newest undone operation of `owner` and returns its label.
+    pub fn redo(&mut self, owner: Option<&str>, reminders: &mut Vec<Reminder>) -> Option<String> {
+        let position = self
+            .redo
+            .iter()
+            .rposition(|operation| operation.owner() == owner)?;
+        let operation = self.redo.remove(position);
+        operation.reapply(reminders);
+        let label = operation.label.clone();
+        self.undo.push(operation);
+        if self.undo.len() > HISTORY_LENGTH {
+            self.undo.remove(0);
+        }
+        Some(label)
+    }
+    /// What the next undo and redo of `owner` would do.
+    #[must_use]
+    pub fn preview(&self, owner: Option<&str>) -> HistoryPreview {
+        let newest_label = |operations: &[Operation]| {
+            operations
+                .iter()
+                .rev()
+                .find(|operation| operation.owner() == owner)
+                .map(|operation| operation.label.clone())
+        };
+        HistoryPreview {
+            undo: newest_label(&self.undo),
+            redo: newest_label(&self.redo),
+        }
+    }
+}
+
+/// The labels of the operations, that the next undo and redo would revert and reapply.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct HistoryPreview {
+    pub undo: Option<String>,
+    pub redo: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, Recording, HISTORY_LENGTH};
+    use crate::{
+        api_error::ApiError,
+        reminder::Reminder,
+        test_reminders::{names, reminder},
+    };
+
+    #[test]
+    fn recording_only_contains_changed_reminders() -> Result<(), ApiError> {
+        let mut reminders = vec![reminder(1, "tea")?, reminder(2, "laundry")?];
+        let recording = Recording::start(&reminders, &[1, 2]);
+        if let Some(tea) = reminders.get_mut(0) {
+            tea.set_name(String::from("green tea"));
+        }
+        let operation = recording.finish("rename", None, &reminders);
+        assert_eq!(
+            operation.as_ref().map(super::Operation::label),
+            Some("rename (green tea)")
+        );
+        assert!(Recording::start(&reminders, &[1, 2])
+            .finish("rename", None, &reminders)
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn undo_reverts_only_the_recorded_fields() -> Result<(), ApiError> {
+        let mut reminders = vec![reminder(1, "tea")?];
+        let mut history = History::default();
+        let recording = Recording::start(&reminders, &[1]);
+        if let Some(tea) = reminders.get_mut(0) {
+            tea.set_name(String::from("green tea"));
+        }
+        if let Some(operation) = recording.finish("rename", None, &reminders) {
+            history.push(operation);
+        }
+        // Changed by someone else, after the rename was recorded.
+        if let Some(tea) = reminders.get_mut(0) {
+            tea.set_description(String::from("two bags"));
+        }
+        assert_eq!(
+            history.undo(None, &mut reminders),
+            Some(String::from("rename (green tea)"))
+        );
+        assert_eq!(names(&reminders), vec!["tea"]);
+        assert_eq!(
+            reminders.first().map(Reminder::description),
+            Some("two bags")
+        );
+        assert_eq!(history.undo(None, &mut reminders), None);
+        Ok(())
+    }
+
+    #[test]
+    fn redo_reapplies_an_undone_operation() -> Result<(), ApiError> {
+        let mut reminders = vec![reminder(1, "tea")?];
+        let mut history = History::default();
+        let recording = Recording::start(&reminders, &[2]);
+        reminders.push(reminder(2, "laundry")?);
+        if let Some(operation) = recording.finish("create", None, &reminders) {
+            history.push(operation);
+        }
+        history.undo(None, &mut reminders);
+        assert_eq!(names(&reminders), vec!["tea"]);
+        assert_eq!(
+            history.preview(None).redo,
+            Some(String::from("create (laundry)"))
+        );
+        assert_eq!(
+            history.redo(None, &mut reminders),
+            Some(String::from("create (laundry)"))
+        );
+        assert_eq!(names(&reminders), vec!["tea", "laundry"]);
+        assert_eq!(history.redo(None, &mut reminders), None);
+        Ok(())
+    }
+
+    #[test]
+    fn users_only_undo_their_own_operations() -> Result<(), ApiError> {
+        let mut reminders = vec![reminder(1, "tea")?];
+        let mut history = History::default();
+        let recording = Recording::start(&reminders, &[1]);
+        if let Some(tea) = reminders.get_mut(0) {
+            tea.set_name(String::from("green tea"));
+        }
+        if let Some(operation) = recording.finish("rename", Some(String::from("ann")), &reminders) {
+            history.push(operation);
+        }
+        assert_eq!(history.undo(Some("bob"), &mut reminders), None);
+        assert_eq!(names(&reminders), vec!["green tea"]);
+        Ok(())
+    }
+
+    #[test]
+    fn redo_keeps_the_undo_stack_capped() -> Result<(), ApiError> {
+        let mut reminders = vec![reminder(1, "tea")?];
+        let mut history = History::default();
+        for i in 0..HISTORY_LENGTH {
+            let recording = Recording::start(&reminders, &[1]);
+            if let Some(tea) = reminders.get_mut(0) {
+                tea.set_name(format!("tea {i}"));
+            }
+            if let Some(operation) =
+                recording.finish("rename", Some(String::from("ann")), &reminders)
+            {
+                history.push(operation);
+            }
+        }
+        history.undo(Some("ann"), &mut reminders);
+        history.push(
+            Recording::start(&[], &[2])
+                .finish(
+                    "create",
+                    Some(String::from("bob")),
+                    &[reminder(2, "laundry")?],
+                )
+                .ok_or_else(|| ApiError::internal("nothing recorded"))?,
+        );
+        history.redo(Some("ann"), &mut reminders);
+        let mut undoable = 0;
+        while history.undo(Some("ann"), &mut reminders).is_some()
+            || history.undo(Some("bob"), &mut reminders).is_some()
+        {
+            undoable += 1;
+        }
+        assert_eq!(undoable, HISTORY_LENGTH);
+        Ok(())
+    }
+}
@@ -3,12 +3,30 @@ use json_store_rs::home_dir;
 use reminder::Reminder;
 use std::path::PathBuf;
 
+pub mod api_error;
+pub mod archive;
+pub mod chain;
+pub mod checklist;
+pub mod completion;
+pub mod delivery;
+pub mod history;
+pub mod library;
 pub mod past_event;
+pub mod pomodoro;
+pub mod priority;
+pub mod recurrence;
 pub mod reminder;
+pub mod stream_event;
+pub mod tag;
+#[cfg(test)]
+mod test_reminders;
+pub mod time_input;
+pub mod timezone;
 
 pub const ROOT_PATH: &str = ".remindy";
 pub const REMINDER_DB_FILE: &str = "reminders.json";
 pub const REMINDER_LIBRARY_FILE: &str = "reminders-library.json";
+pub const REMINDER_ARCHIVE_FILE: &str = "reminders-archive.json";
 pub const AUDIO_FILE: &str = "ring_tone.mp3";
 
 #[cfg(not(debug_assertions))]
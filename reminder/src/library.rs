@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    priority::Priority,
+    recurrence::Recurrence,
+    reminder::{my_local_offset, ApiReminder, Reminder, ReminderType},
+};
+
+/// A reusable blueprint of a reminder, stored in the `REMINDER_LIBRARY_FILE`.
+/// Instantiating a template creates a fresh reminder relative to now.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReminderTemplate {
+    name: String,
+    description: String,
+    reminder_type: ReminderType,
+    duration: Duration,
+    finish_time: OffsetDateTime,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    checklist: Vec<String>,
+}
+
+impl ReminderTemplate {
+    #[must_use]
+    pub fn from_reminder(reminder: &Reminder) -> Self {
+        Self {
+            name: reminder.name().trim_end().to_string(),
+            description: reminder.description().to_string(),
+            reminder_type: reminder.reminder_type().clone(),
+            duration: reminder.whole_duration(),
+            finish_time: reminder.finish_time(),
+            owner: reminder.owner().map(String::from),
+            tags: reminder.tags().to_vec(),
+            priority: reminder.priority(),
+            checklist: reminder
+                .checklist()
+                .iter()
+                .map(|item| item.text().to_string())
+                .collect(),
+        }
+    }
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+    #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+    pub fn set_owner(&mut self, owner: Option<String>) {
+        self.owner = owner;
+    }
+    #[must_use]
+    pub fn reminder_type(&self) -> &ReminderType {
+        &self.reminder_type
+    }
+    /// Builds the `ApiReminder` for a new reminder from this template.
+    /// `Duration` templates run for the stored duration starting now,
+    /// `Time` templates end at the stored time of day (today, or tomorrow if that already passed)
+    /// and `Date` templates end on their stored date, moved to the next year once that passed.
+    #[must_use]
+    pub fn to_api_reminder(&self) -> ApiReminder {
+        self.to_api_reminder_at(OffsetDateTime::now_utc().to_offset(my_local_offset()))
+    }
+    #[allow(clippy::arithmetic_side_effects)]
+    fn to_api_reminder_at(&self, now: OffsetDateTime) -> ApiReminder {
+        let finish_time = match self.reminder_type {
+            ReminderType::Duration => now + self.duration,
+            // `Reminder::from_api_reminder` moves it to the next time the clock shows it
+            ReminderType::Time => self.finish_time,
+            ReminderType::Date if self.finish_time > now => self.finish_time,
+            ReminderType::Date => Recurrence::Yearly
+                .next_finish_time(self.finish_time, now)
+                .unwrap_or(now),
+            ReminderType::Note => now,
+        };
+        ApiReminder {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            finish_time,
+            reminder_type: self.reminder_type.clone(),
+            tags: self.tags.clone(),
+            priority: self.priority,
+            checklist: self.checklist.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use super::ReminderTemplate;
+    use crate::{api_error::ApiError, reminder::ReminderType, test_reminders::reminder};
+
+    fn template(
+        reminder_type: ReminderType,
+        finish_time: time::OffsetDateTime,
+    ) -> Result<ReminderTemplate, ApiError> {
+        let mut template = ReminderTemplate::from_reminder(&reminder(1, "template")?);
+        template.reminder_type = reminder_type;
+        template.finish_time = finish_time;
+        template.duration = Duration::minutes(25);
+        Ok(template)
+    }
+
+    #[test]
+    fn past_date_templates_move_to_the_next_year() -> Result<(), ApiError> {
+        let template = template(ReminderType::Date, datetime!(2023-03-14 9:00 UTC))?;
+        let api_reminder = template.to_api_reminder_at(datetime!(2025-06-01 12:00 UTC));
+        assert_eq!(api_reminder.finish_time, datetime!(2026-03-14 9:00 UTC));
+        let api_reminder = template.to_api_reminder_at(datetime!(2025-01-01 12:00 UTC));
+        assert_eq!(api_reminder.finish_time, datetime!(2025-03-14 9:00 UTC));
+        Ok(())
+    }
+
+    #[test]
+    fn future_date_templates_keep_their_date() -> Result<(), ApiError> {
+        let template = template(ReminderType::Date, datetime!(2030-03-14 9:00 UTC))?;
+        let api_reminder = template.to_api_reminder_at(datetime!(2025-06-01 12:00 UTC));
+        assert_eq!(api_reminder.finish_time, datetime!(2030-03-14 9:00 UTC));
+        Ok(())
+    }
+
+    #[test]
+    fn duration_templates_start_now() -> Result<(), ApiError> {
+        let template = template(ReminderType::Duration, datetime!(2023-03-14 9:00 UTC))?;
+        let api_reminder = template.to_api_reminder_at(datetime!(2025-06-01 12:00 UTC));
+        assert_eq!(api_reminder.finish_time, datetime!(2025-06-01 12:25 UTC));
+        Ok(())
+    }
+}
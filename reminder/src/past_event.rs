@@ -2,25 +2,64 @@ use std::fmt::Display;
 
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::{format_description, OffsetDateTime};
 
-use crate::reminder::{my_local_offset, Reminder};
+use crate::{
+    library::ReminderTemplate,
+    reminder::{my_local_offset, Reminder},
+    time_input::format_duration,
+    timezone::to_local,
+};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PastEvent {
     ReminderEnded(Reminder),
     ReminderRepeatToggle(Reminder),
+    ReminderSendEMessageToggle(Reminder),
     WrongInput,
     InternalError,
     TryResetDateReminder(Reminder),
     ReminderCreated(Reminder),
+    TemplateSaved(ReminderTemplate),
     ReminderEdited(Reminder),
     ReminderDeleted(Reminder),
     ReminderSnooze(Reminder),
     ReminderPause(Reminder),
+    ReminderArchived(Reminder),
+    ReminderRestored(Reminder),
+    ReminderWarning(Reminder),
+    ReminderNagToggle(Reminder),
+    ReminderNagged(Reminder),
+    ReminderChainContinued(Reminder),
     None,
 }
+impl PastEvent {
+    /// The owner of the reminder or template this event is about.
+    #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            PastEvent::ReminderEnded(reminder)
+            | PastEvent::ReminderRepeatToggle(reminder)
+            | PastEvent::ReminderSendEMessageToggle(reminder)
+            | PastEvent::TryResetDateReminder(reminder)
+            | PastEvent::ReminderCreated(reminder)
+            | PastEvent::ReminderEdited(reminder)
+            | PastEvent::ReminderDeleted(reminder)
+            | PastEvent::ReminderSnooze(reminder)
+            | PastEvent::ReminderPause(reminder)
+            | PastEvent::ReminderArchived(reminder)
+            | PastEvent::ReminderRestored(reminder)
+            | PastEvent::ReminderWarning(reminder)
+            | PastEvent::ReminderNagToggle(reminder)
+            | PastEvent::ReminderNagged(reminder)
+            | PastEvent::ReminderChainContinued(reminder) => reminder.owner(),
+            PastEvent::TemplateSaved(template) => template.owner(),
+            PastEvent::WrongInput | PastEvent::InternalError | PastEvent::None => None,
+        }
+    }
+}
 impl Display for PastEvent {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             #[allow(clippy::arithmetic_side_effects)]
@@ -60,6 +99,12 @@ impl Display for PastEvent {
                 reminder.name().bright_green(),
                 "created".bright_green()
             ),
+            PastEvent::TemplateSaved(template) => write!(
+                f,
+                "{} {}",
+                template.name().bright_green(),
+                "saved to library".bright_green()
+            ),
             PastEvent::ReminderEdited(reminder) => {
                 write!(f, "{} {}", reminder.name().blue(), "edited".blue())
             }
@@ -67,15 +112,145 @@ impl Display for PastEvent {
                 write!(f, "{} {}", reminder.name().red(), "deleted".red())
             }
             PastEvent::ReminderSnooze(reminder) => {
-                write!(f, "{} {}", reminder.name().blue(), "snoozed".blue())
+                write!(
+                    f,
+                    "{} {}",
+                    reminder.name().blue(),
+                    format!("snoozed ({}x)", reminder.snooze_count()).blue()
+                )
             }
             PastEvent::ReminderPause(reminder) => {
                 write!(f, "{} {}", reminder.name().blue(), "paused/unpaused".blue())
             }
+            PastEvent::ReminderArchived(reminder) => {
+                write!(f, "{} {}", reminder.name().red(), "archived".red())
+            }
+            PastEvent::ReminderRestored(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().bright_green(),
+                "restored from the archive".bright_green()
+            ),
+            PastEvent::ReminderWarning(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().yellow(),
+                format!(
+                    "due in {}",
+                    format_duration(reminder.warning_needs_confirmation().unwrap_or_default())
+                )
+                .yellow()
+            ),
+            PastEvent::ReminderNagToggle(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().blue(),
+                if reminder.nag() {
+                    "will nag until confirmed".blue()
+                } else {
+                    "will not nag".blue()
+                }
+            ),
+            PastEvent::ReminderNagged(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().bright_red(),
+                format!("still not confirmed ({}x)", reminder.nag_count()).bright_red()
+            ),
+            PastEvent::ReminderChainContinued(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().bright_green(),
+                "started, its chain continues".bright_green()
+            ),
             PastEvent::ReminderRepeatToggle(reminder) => {
                 write!(f, "{} {}", reminder.name().blue(), "repeat toggled".blue())
             }
+            PastEvent::ReminderSendEMessageToggle(reminder) => write!(
+                f,
+                "{} {}",
+                reminder.name().blue(),
+                if reminder.send_e_message() {
+                    "will send external messages".blue()
+                } else {
+                    "will not send external messages".blue()
+                }
+            ),
             PastEvent::None => write!(f, "{}", " ".black()),
         }
     }
 }
+
+/// The amount of events kept in the `EventLog`, older ones get dropped.
+pub const EVENT_LOG_LENGTH: usize = 100;
+
+/// A `PastEvent` with a sequence id and the time it happened.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LoggedEvent {
+    id: usize,
+    time: OffsetDateTime,
+    event: PastEvent,
+}
+impl LoggedEvent {
+    #[must_use]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    #[must_use]
+    pub fn time(&self) -> OffsetDateTime {
+        self.time
+    }
+    #[must_use]
+    pub fn event(&self) -> &PastEvent {
+        &self.event
+    }
+}
+impl Display for LoggedEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Ok(time_format) = format_description::parse("[hour]:[minute]:[second]") else {
+            return Err(std::fmt::Error);
+        };
+        let Ok(time) = to_local(self.time).format(&time_format) else {
+            return Err(std::fmt::Error);
+        };
+        write!(f, "{} {}", time.bright_black(), self.event)
+    }
+}
+
+/// A bounded, persisted log of everything that happened to the reminders.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct EventLog {
+    last_id: usize,
+    events: Vec<LoggedEvent>,
+}
+impl EventLog {
+    pub fn push(&mut self, event: PastEvent) {
+        self.last_id = self.last_id.saturating_add(1);
+        self.events.push(LoggedEvent {
+            id: self.last_id,
+            time: OffsetDateTime::now_utc().to_offset(my_local_offset()),
+            event,
+        });
+        if self.events.len() > EVENT_LOG_LENGTH {
+            self.events.remove(0);
+        }
+    }
+    /// The id of the newest event, `0` if nothing happened yet.
+    #[must_use]
+    pub fn last_id(&self) -> usize {
+        self.last_id
+    }
+    #[must_use]
+    pub fn last(&self) -> Option<&LoggedEvent> {
+        self.events.last()
+    }
+    /// Every event still in the log with an id greater than `id`.
+    #[must_use]
+    pub fn since(&self, id: usize) -> Vec<LoggedEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.id > id)
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+/// A pomodoro session running on a repeating `Duration` reminder.
+/// Work and short breaks alternate, until `cycles` pomodoros are done and the long break ends the session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PomodoroSession {
+    phase: PomodoroPhase,
+    /// The pomodoros, whose work phase ended.
+    completed: u32,
+    cycles: u32,
+    work: Duration,
+    short_break: Duration,
+    long_break: Duration,
+}
+
+impl PomodoroSession {
+    /// Starts with the first work phase, a session has at least one pomodoro.
+    #[must_use]
+    pub fn new(work: Duration, short_break: Duration, long_break: Duration, cycles: u32) -> Self {
+        Self {
+            phase: PomodoroPhase::Work,
+            completed: 0,
+            cycles: cycles.max(1),
+            work,
+            short_break,
+            long_break,
+        }
+    }
+    #[must_use]
+    pub fn phase(&self) -> PomodoroPhase {
+        self.phase
+    }
+    #[must_use]
+    pub fn completed(&self) -> u32 {
+        self.completed
+    }
+    #[must_use]
+    pub fn cycles(&self) -> u32 {
+        self.cycles
+    }
+    /// How long the current phase lasts.
+    #[must_use]
+    pub fn phase_duration(&self) -> Duration {
+        match self.phase {
+            PomodoroPhase::Work => self.work,
+            PomodoroPhase::ShortBreak => self.short_break,
+            PomodoroPhase::LongBreak => self.long_break,
+        }
+    }
+    /// Moves on to the next phase and returns how long it lasts, `None` once the long break ended the session.
+    pub fn advance(&mut self) -> Option<Duration> {
+        match self.phase {
+            PomodoroPhase::Work => {
+                self.completed = self.completed.saturating_add(1);
+                self.phase = if self.completed >= self.cycles {
+                    PomodoroPhase::LongBreak
+                } else {
+                    PomodoroPhase::ShortBreak
+                };
+            }
+            PomodoroPhase::ShortBreak => self.phase = PomodoroPhase::Work,
+            PomodoroPhase::LongBreak => return None,
+        }
+        Some(self.phase_duration())
+    }
+}
+
+impl Display for PomodoroSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.phase {
+            PomodoroPhase::Work => write!(
+                f,
+                "work {}/{}",
+                self.completed.saturating_add(1),
+                self.cycles
+            ),
+            PomodoroPhase::ShortBreak => {
+                write!(f, "short break, {}/{} done", self.completed, self.cycles)
+            }
+            PomodoroPhase::LongBreak => write!(f, "long break, {} done", self.completed),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::reminder::{my_local_offset, Reminder};
+
+/// How important a reminder is, decides how loud it alerts and where it is sorted to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Only shows a message, without sound or switching the workspace.
+    Low,
+    #[default]
+    Normal,
+    /// Plays the sound at full volume.
+    High,
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ();
+
+    /// Parses `low | normal | high`, the first letter is enough.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "low" | "l" => Ok(Priority::Low),
+            "normal" | "n" => Ok(Priority::Normal),
+            "high" | "h" => Ok(Priority::High),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The order reminders are listed in. Both put the latest finish time first and due reminders last.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Only by finish time.
+    #[default]
+    Due,
+    /// Upcoming and due reminders are kept apart, inside of them the highest priority comes last,
+    /// right next to the due ones.
+    Priority,
+}
+
+impl SortOrder {
+    /// Sorts `reminders` with a single `now`, so a reminder can not become due in the middle of the sort.
+    pub fn sort(&self, reminders: &mut [Reminder]) {
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        reminders.sort_by(|a, b| self.compare(a, b, now));
+    }
+    /// Compares two reminders as they are at `now`.
+    #[must_use]
+    pub fn compare(&self, a: &Reminder, b: &Reminder, now: OffsetDateTime) -> Ordering {
+        let by_finish_time = b.finish_time().cmp(&a.finish_time());
+        match self {
+            SortOrder::Due => by_finish_time,
+            SortOrder::Priority => is_due(a, now)
+                .cmp(&is_due(b, now))
+                .then(a.priority().cmp(&b.priority()))
+                .then(by_finish_time),
+        }
+    }
+    /// The order 'o' switches to.
+    #[must_use]
+    pub fn next(&self) -> Self {
+        match self {
+            SortOrder::Due => SortOrder::Priority,
+            SortOrder::Priority => SortOrder::Due,
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Due => write!(f, "due"),
+            SortOrder::Priority => write!(f, "priority"),
+        }
+    }
+}
+
+/// Like `Reminder::remaining_duration` being `None`, but at `now`.
+fn is_due(reminder: &Reminder, now: OffsetDateTime) -> bool {
+    reminder.finish_time() <= now && !reminder.is_note()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime};
+
+    use super::{Priority, SortOrder};
+    use crate::{
+        api_error::ApiError,
+        reminder::Reminder,
+        test_reminders::{names, reminder},
+    };
+
+    fn reminder_at(
+        name: &str,
+        finish_time: OffsetDateTime,
+        priority: Priority,
+    ) -> Result<Reminder, ApiError> {
+        let mut reminder = reminder(1, name)?;
+        reminder.set_finish_time(finish_time);
+        reminder.set_priority(priority);
+        Ok(reminder)
+    }
+
+    fn sorted(
+        sort_order: SortOrder,
+        mut reminders: Vec<Reminder>,
+        now: OffsetDateTime,
+    ) -> Vec<String> {
+        reminders.sort_by(|a, b| sort_order.compare(a, b, now));
+        names(&reminders).into_iter().map(String::from).collect()
+    }
+
+    #[test]
+    fn priority_keeps_due_reminders_last() -> Result<(), ApiError> {
+        let now = OffsetDateTime::now_utc();
+        let reminders = vec![
+            reminder_at("due", now.saturating_sub(Duration::hours(1)), Priority::Low)?,
+            reminder_at(
+                "high",
+                now.saturating_add(Duration::hours(2)),
+                Priority::High,
+            )?,
+            reminder_at("low", now.saturating_add(Duration::hours(1)), Priority::Low)?,
+        ];
+        assert_eq!(
+            sorted(SortOrder::Priority, reminders.clone(), now),
+            vec!["low", "high", "due"]
+        );
+        assert_eq!(
+            sorted(SortOrder::Due, reminders, now),
+            vec!["high", "low", "due"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_finishing_at_now_are_due() -> Result<(), ApiError> {
+        let now = OffsetDateTime::now_utc();
+        let reminders = vec![
+            reminder_at("now", now, Priority::Low)?,
+            reminder_at(
+                "later",
+                now.saturating_add(Duration::hours(1)),
+                Priority::High,
+            )?,
+        ];
+        assert_eq!(
+            sorted(SortOrder::Priority, reminders.clone(), now),
+            vec!["later", "now"]
+        );
+        // A second earlier both are upcoming, so the low priority comes first
+        assert_eq!(
+            sorted(
+                SortOrder::Priority,
+                reminders,
+                now.saturating_sub(Duration::SECOND)
+            ),
+            vec!["now", "later"]
+        );
+        Ok(())
+    }
+}
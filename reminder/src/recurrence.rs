@@ -0,0 +1,400 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use time::{util::days_in_year_month, Date, Duration, Month, OffsetDateTime, Weekday};
+
+use crate::timezone::{at_local_time, to_local};
+
+/// Describes how a repeating reminder computes its next finish time once it ended.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Recurrence {
+    /// Restarts with the same duration (`Duration`) or at the same time tomorrow (`Time`).
+    #[default]
+    Restart,
+    EveryMinutes(u32),
+    EveryHours(u32),
+    EveryDays(u32),
+    Weekdays(Vec<Weekday>),
+    /// Monthly on the given day, clamped to the last day of shorter months.
+    Monthly(u8),
+    /// Yearly on the same day and month (birthdays).
+    Yearly,
+}
+
+/// Fixed intervals longer than this are rejected, `yearly` covers anything longer.
+pub const MAX_INTERVAL_DAYS: i64 = 366;
+
+impl Recurrence {
+    /// If the rule can ever occur: intervals between one minute and `MAX_INTERVAL_DAYS`,
+    /// at least one weekday and a day of the month between 1 and 31.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Recurrence::Restart | Recurrence::Yearly => true,
+            Recurrence::EveryMinutes(_) | Recurrence::EveryHours(_) | Recurrence::EveryDays(_) => {
+                self.interval().is_some_and(|interval| {
+                    interval.is_positive() && interval <= Duration::days(MAX_INTERVAL_DAYS)
+                })
+            }
+            Recurrence::Weekdays(weekdays) => !weekdays.is_empty(),
+            Recurrence::Monthly(day) => (1..=31).contains(day),
+        }
+    }
+    fn interval(&self) -> Option<Duration> {
+        match self {
+            Recurrence::EveryMinutes(amount) => Some(Duration::minutes(i64::from(*amount))),
+            Recurrence::EveryHours(amount) => Some(Duration::hours(i64::from(*amount))),
+            Recurrence::EveryDays(amount) => Some(Duration::days(i64::from(*amount))),
+            Recurrence::Restart
+            | Recurrence::Weekdays(_)
+            | Recurrence::Monthly(_)
+            | Recurrence::Yearly => None,
+        }
+    }
+    /// Computes the first occurrence after `now`, keeping the local wall clock time of `finish_time` across DST changes.
+    /// Returns `None` for `Restart` (which depends on the reminder type), for invalid rules
+    /// and once the next occurrence is out of the representable range.
+    #[must_use]
+    pub fn next_finish_time(
+        &self,
+        finish_time: OffsetDateTime,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        if !self.is_valid() {
+            return None;
+        }
+        match self {
+            Recurrence::Restart => None,
+            Recurrence::EveryMinutes(_) | Recurrence::EveryHours(_) | Recurrence::EveryDays(_) => {
+                next_by_interval(finish_time, now, self.interval()?)
+            }
+            Recurrence::Weekdays(weekdays) => {
+                let time = to_local(finish_time).time();
+                let mut date = if finish_time < now {
+                    to_local(now).date().previous_day()?
+                } else {
+                    to_local(finish_time).date()
+                };
+                // Today and the following seven days contain every weekday.
+                for _ in 0..8 {
+                    date = date.next_day()?;
+                    let next = at_local_time(date, time);
+                    if next > now && weekdays.contains(&date.weekday()) {
+                        return Some(next);
+                    }
+                }
+                None
+            }
+            Recurrence::Monthly(day) => {
+                let local_finish_time = to_local(finish_time);
+                let (mut year, mut month) = (local_finish_time.year(), local_finish_time.month());
+                loop {
+                    let clamped_day = (*day).min(days_in_year_month(year, month));
+                    let date = Date::from_calendar_date(year, month, clamped_day).ok()?;
+                    let next = at_local_time(date, local_finish_time.time());
+                    if next > now && next > finish_time {
+                        return Some(next);
+                    }
+                    if month == Month::December {
+                        year = year.checked_add(1)?;
+                    }
+                    month = month.next();
+                }
+            }
+            Recurrence::Yearly => {
+                let local_finish_time = to_local(finish_time);
+                let mut year = local_finish_time.year();
+                loop {
+                    year = year.checked_add(1)?;
+                    let clamped_day = local_finish_time
+                        .day()
+                        .min(days_in_year_month(year, local_finish_time.month()));
+                    let date =
+                        Date::from_calendar_date(year, local_finish_time.month(), clamped_day)
+                            .ok()?;
+                    let next = at_local_time(date, local_finish_time.time());
+                    if next > now {
+                        return Some(next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Skips every interval missed since `finish_time`, so a reminder that was due long ago
+/// lands on its first occurrence after `now` instead of firing for every missed one.
+fn next_by_interval(
+    finish_time: OffsetDateTime,
+    now: OffsetDateTime,
+    interval: Duration,
+) -> Option<OffsetDateTime> {
+    if !interval.is_positive() {
+        return None;
+    }
+    if finish_time > now {
+        return finish_time.checked_add(interval);
+    }
+    let elapsed = now
+        .unix_timestamp_nanos()
+        .checked_sub(finish_time.unix_timestamp_nanos())?;
+    let missed_intervals = elapsed.checked_div(interval.whole_nanoseconds())?;
+    let skipped_intervals = i32::try_from(missed_intervals.checked_add(1)?).ok()?;
+    finish_time.checked_add(interval.checked_mul(skipped_intervals)?)
+}
+
+impl FromStr for Recurrence {
+    type Err = ();
+
+    /// Parses `restart | 30m | 2h | 3d | daily | weekly | mon,fri | monthly 15 | yearly`.
+    /// A leading `every` is ignored, rules that can never occur (see `is_valid`) are rejected.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_recurrence(input)
+            .filter(Recurrence::is_valid)
+            .ok_or(())
+    }
+}
+
+fn parse_recurrence(input: &str) -> Option<Recurrence> {
+    let input = input.trim().to_lowercase();
+    let input = input.strip_prefix("every").unwrap_or(&input).trim();
+    match input {
+        "restart" => return Some(Recurrence::Restart),
+        "hourly" => return Some(Recurrence::EveryHours(1)),
+        "daily" => return Some(Recurrence::EveryDays(1)),
+        "weekly" => return Some(Recurrence::EveryDays(7)),
+        "yearly" => return Some(Recurrence::Yearly),
+        _ => (),
+    }
+    if let Some(day) = input.strip_prefix("monthly") {
+        return day
+            .trim()
+            .trim_end_matches('.')
+            .parse()
+            .map(Recurrence::Monthly)
+            .ok();
+    }
+    if let Some(Ok(amount)) = input.strip_suffix('m').map(str::parse) {
+        return Some(Recurrence::EveryMinutes(amount));
+    }
+    if let Some(Ok(amount)) = input.strip_suffix('h').map(str::parse) {
+        return Some(Recurrence::EveryHours(amount));
+    }
+    if let Some(Ok(amount)) = input.strip_suffix('d').map(str::parse) {
+        return Some(Recurrence::EveryDays(amount));
+    }
+    input
+        .split(',')
+        .map(|weekday| parse_weekday(weekday.trim()))
+        .collect::<Option<Vec<Weekday>>>()
+        .map(Recurrence::Weekdays)
+}
+
+/// Parses english weekday names, the first three letters are enough.
+#[must_use]
+pub fn parse_weekday(input: &str) -> Option<Weekday> {
+    let input = input.to_lowercase();
+    if input.len() < 3 {
+        return None;
+    }
+    [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ]
+    .into_iter()
+    .find(|weekday| {
+        weekday
+            .to_string()
+            .to_lowercase()
+            .starts_with(input.as_str())
+    })
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Restart => write!(f, "restart"),
+            Recurrence::EveryMinutes(amount) => write!(f, "every {amount}m"),
+            Recurrence::EveryHours(amount) => write!(f, "every {amount}h"),
+            Recurrence::EveryDays(amount) => write!(f, "every {amount}d"),
+            Recurrence::Weekdays(weekdays) => write!(
+                f,
+                "every {}",
+                weekdays
+                    .iter()
+                    .map(|weekday| weekday.to_string().chars().take(3).collect::<String>())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Recurrence::Monthly(day) => write!(f, "monthly on the {day}."),
+            Recurrence::Yearly => write!(f, "yearly"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Weekday};
+
+    use super::Recurrence;
+
+    #[test]
+    fn interval_catches_up_on_missed_occurrences() {
+        let finish_time = datetime!(2024-03-01 08:00 UTC);
+        let now = datetime!(2024-03-01 10:30 UTC);
+        assert_eq!(
+            Recurrence::EveryHours(1).next_finish_time(finish_time, now),
+            Some(datetime!(2024-03-01 11:00 UTC))
+        );
+        assert_eq!(
+            Recurrence::EveryMinutes(45).next_finish_time(finish_time, now),
+            Some(datetime!(2024-03-01 11:00 UTC))
+        );
+        assert_eq!(
+            Recurrence::EveryHours(1)
+                .next_finish_time(finish_time, datetime!(2024-03-01 10:00 UTC)),
+            Some(datetime!(2024-03-01 11:00 UTC))
+        );
+    }
+
+    #[test]
+    fn interval_before_the_finish_time_adds_one_interval() {
+        assert_eq!(
+            Recurrence::EveryDays(2).next_finish_time(
+                datetime!(2024-03-01 08:00 UTC),
+                datetime!(2024-03-01 07:00 UTC)
+            ),
+            Some(datetime!(2024-03-03 08:00 UTC))
+        );
+    }
+
+    #[test]
+    fn weekdays_skip_to_the_next_listed_day() {
+        // 2024-03-01 is a friday.
+        let finish_time = datetime!(2024-03-01 08:00 UTC);
+        let now = datetime!(2024-03-01 09:00 UTC);
+        let monday_and_wednesday = Recurrence::Weekdays(vec![Weekday::Monday, Weekday::Wednesday]);
+        assert_eq!(
+            monday_and_wednesday.next_finish_time(finish_time, now),
+            Some(datetime!(2024-03-04 08:00 UTC))
+        );
+        assert_eq!(
+            Recurrence::Weekdays(vec![Weekday::Friday]).next_finish_time(finish_time, now),
+            Some(datetime!(2024-03-08 08:00 UTC))
+        );
+        assert_eq!(
+            monday_and_wednesday.next_finish_time(
+                datetime!(2024-02-20 08:00 UTC),
+                datetime!(2024-03-04 07:00 UTC)
+            ),
+            Some(datetime!(2024-03-04 08:00 UTC))
+        );
+    }
+
+    #[test]
+    fn monthly_clamps_to_the_last_day_of_the_month() {
+        let end_of_month = Recurrence::Monthly(31);
+        assert_eq!(
+            end_of_month.next_finish_time(
+                datetime!(2024-01-31 08:00 UTC),
+                datetime!(2024-01-31 09:00 UTC)
+            ),
+            Some(datetime!(2024-02-29 08:00 UTC))
+        );
+        assert_eq!(
+            end_of_month.next_finish_time(
+                datetime!(2023-01-31 08:00 UTC),
+                datetime!(2023-01-31 09:00 UTC)
+            ),
+            Some(datetime!(2023-02-28 08:00 UTC))
+        );
+        assert_eq!(
+            end_of_month.next_finish_time(
+                datetime!(2024-02-29 08:00 UTC),
+                datetime!(2024-02-29 09:00 UTC)
+            ),
+            // The tests run in `Europe/Berlin`, which switches to summer time that day
+            Some(datetime!(2024-03-31 07:00 UTC))
+        );
+    }
+
+    #[test]
+    fn yearly_clamps_leap_days() {
+        assert_eq!(
+            Recurrence::Yearly.next_finish_time(
+                datetime!(2024-02-29 08:00 UTC),
+                datetime!(2024-02-29 09:00 UTC)
+            ),
+            Some(datetime!(2025-02-28 08:00 UTC))
+        );
+    }
+
+    #[test]
+    fn calendar_rules_keep_the_wall_clock_across_dst_changes() {
+        // 08:00 in Berlin, the week before summer time starts on 2024-03-31
+        let finish_time = datetime!(2024-03-25 08:00 +1);
+        let now = datetime!(2024-03-25 09:00 +1);
+        assert_eq!(
+            Recurrence::Weekdays(vec![Weekday::Monday]).next_finish_time(finish_time, now),
+            Some(datetime!(2024-04-01 08:00 +2))
+        );
+        assert_eq!(
+            Recurrence::Monthly(25).next_finish_time(finish_time, now),
+            Some(datetime!(2024-04-25 08:00 +2))
+        );
+        // Stored with the summer offset, the next one is in winter
+        assert_eq!(
+            Recurrence::Weekdays(vec![Weekday::Monday]).next_finish_time(
+                datetime!(2024-10-21 08:00 +2),
+                datetime!(2024-10-28 07:00 +2)
+            ),
+            Some(datetime!(2024-10-28 08:00 +1))
+        );
+        assert_eq!(
+            Recurrence::Yearly.next_finish_time(
+                datetime!(2024-07-01 08:00 +2),
+                datetime!(2024-12-01 08:00 +1)
+            ),
+            Some(datetime!(2025-07-01 08:00 +2))
+        );
+    }
+
+    #[test]
+    fn unreasonable_rules_are_rejected() {
+        assert!(!Recurrence::EveryDays(u32::MAX).is_valid());
+        assert!(!Recurrence::EveryMinutes(0).is_valid());
+        assert!(!Recurrence::Weekdays(vec![]).is_valid());
+        assert!(!Recurrence::Monthly(32).is_valid());
+        assert_eq!("4294967295d".parse::<Recurrence>(), Err(()));
+        assert_eq!("0m".parse::<Recurrence>(), Err(()));
+        assert_eq!("monthly 0".parse::<Recurrence>(), Err(()));
+        assert_eq!("366d".parse::<Recurrence>(), Ok(Recurrence::EveryDays(366)));
+        assert_eq!(
+            Recurrence::EveryDays(u32::MAX).next_finish_time(
+                datetime!(2024-03-01 08:00 UTC),
+                datetime!(2024-03-01 09:00 UTC)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn occurrences_out_of_range_are_none() {
+        let finish_time = datetime!(9999-12-31 08:00 UTC);
+        let now = datetime!(9999-12-31 07:00 UTC);
+        assert_eq!(
+            Recurrence::EveryDays(1).next_finish_time(finish_time, now),
+            None
+        );
+        assert_eq!(Recurrence::Yearly.next_finish_time(finish_time, now), None);
+        assert_eq!(
+            Recurrence::Monthly(1).next_finish_time(finish_time, now),
+            None
+        );
+    }
+}
@@ -1,21 +1,32 @@
-use std::{
-    error::Error,
-    fmt::Display,
-    process::Command,
-};
+use std::fmt::Display;
 
 #[cfg(feature = "colored")]
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use time::{format_description, Duration, OffsetDateTime, UtcOffset};
 
-use crate::{map_range, past_event::PastEvent};
+#[cfg(feature = "colored")]
+use crate::tag::tag_color;
+use crate::{
+    api_error::ApiError,
+    checklist::ChecklistItem,
+    map_range,
+    past_event::{EventLog, PastEvent},
+    pomodoro::PomodoroSession,
+    priority::Priority,
+    recurrence::{Recurrence, MAX_INTERVAL_DAYS},
+    tag::{normalize_tag, parse_tags},
+    timezone::{at_local_time, to_local},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TimeObject {
     pub reminder_type: ReminderType,
     pub finish_time: OffsetDateTime,
     pub duration: Duration,
+    /// Replaces the recurrence rule (and enables repeating) when set.
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -24,41 +35,62 @@ pub enum ReminderType {
     Duration,
     Time,
     Date,
-    // TODO: Note
+    /// A scratchpad entry without a finish time, that never gets triggered.
+    Note,
 }
 
+/// The current offset of the local timezone, see `timezone::init_timezone`.
 #[must_use]
 pub fn my_local_offset() -> UtcOffset {
-    let Ok(system_offset_command_output) = Command::new("date").arg("+'%:z'").output() else {
-        println!("Error: Could not compute local offset");
-        return UtcOffset::UTC;
-    };
-    let system_offset = system_offset_command_output.stdout;
-    let Some(sliced_system_offset) = system_offset.get(3) else {
-        println!("Error: Could not compute local offset");
-        return UtcOffset::UTC;
-    };
-    let sliced_system_offset = &[*sliced_system_offset];
-    let Ok(system_offset_hours) = std::str::from_utf8(sliced_system_offset) else {
-        println!("Error: Could not compute local offset");
-        return UtcOffset::UTC;
-    };
-    let Ok(system_offset_hours) = system_offset_hours.parse() else {
-        println!("Error: Could not compute local offset");
-        return UtcOffset::UTC;
-    };
-    match UtcOffset::from_hms(system_offset_hours, 0, 0) {
-        Ok(offset) => offset,
-        Err(e) => {
-            println!("Error: Could not compute local offset");
-            println!("{:?}", e.source());
-            UtcOffset::UTC
+    to_local(OffsetDateTime::now_utc()).offset()
+}
+
+/// The next time the local wall clock shows the time of day of `finish_time`, today or tomorrow.
+/// Follows the wall clock, so the reminder keeps its time of day across DST changes.
+fn next_time_of_day(finish_time: OffsetDateTime, now: OffsetDateTime) -> OffsetDateTime {
+    let time = to_local(finish_time).time();
+    let mut date = to_local(now).date();
+    loop {
+        let next = at_local_time(date, time);
+        if next >= now {
+            return next;
         }
+        let Some(next_date) = date.next_day() else {
+            return next;
+        };
+        date = next_date;
+    }
+}
+
+/// Rejects recurrence rules, that can never occur.
+#[allow(clippy::missing_errors_doc)]
+pub fn check_recurrence(recurrence: &Recurrence) -> Result<(), ApiError> {
+    if recurrence.is_valid() {
+        Ok(())
+    } else {
+        Err(ApiError::invalid_input(format!(
+            "The recurrence '{recurrence}' can never occur, intervals go up to {MAX_INTERVAL_DAYS} days"
+        )))
+    }
+}
+
+/// Rolls time of day reminders forward and rejects durations, that would end before they start.
+fn checked_finish_time(
+    reminder_type: &ReminderType,
+    finish_time: OffsetDateTime,
+    now: OffsetDateTime,
+) -> Result<OffsetDateTime, ApiError> {
+    match reminder_type {
+        ReminderType::Time => Ok(next_time_of_day(finish_time, now)),
+        ReminderType::Duration if finish_time < now => Err(ApiError::invalid_input(
+            "A duration reminder can not end before it starts",
+        )),
+        ReminderType::Duration | ReminderType::Date | ReminderType::Note => Ok(finish_time),
     }
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Reminder {
     id: usize,
     name: String,
@@ -73,7 +105,59 @@ pub struct Reminder {
     restart_flag: bool,
     paused: bool,
     repeating: bool,
+    #[serde(default)]
+    recurrence: Recurrence,
     send_e_message: bool,
+    /// The name of the user this reminder belongs to, `None` for reminders shared by everyone.
+    #[serde(default)]
+    owner: Option<String>,
+    /// How long a snooze pushes this reminder, `None` uses the configured default.
+    #[serde(default)]
+    snooze_duration: Option<Duration>,
+    /// How often the reminder was snoozed since it was last (re)started.
+    #[serde(default)]
+    snooze_count: usize,
+    /// How often the reminder was paused since it was last (re)started.
+    #[serde(default)]
+    pause_count: usize,
+    /// How long before the finish time a heads-up fires, e.g. 15 minutes and 1 minute.
+    #[serde(default)]
+    warnings: Vec<Duration>,
+    /// The lead times, that already fired for the current finish time.
+    #[serde(default)]
+    fired_warnings: Vec<Duration>,
+    /// The lead time of the last heads-up, until it is acknowledged or the reminder is due.
+    #[serde(default)]
+    warning_needs_confirmation: Option<Duration>,
+    /// Keeps alerting every few minutes after the reminder ended, until someone confirms it by hand.
+    #[serde(default)]
+    nag: bool,
+    /// The ids of the clients, that already alerted the user about the current heads-up, end or nag.
+    #[serde(default)]
+    delivered_to: Vec<String>,
+    /// When clients were last asked to alert about this reminder.
+    #[serde(default)]
+    last_alert: Option<OffsetDateTime>,
+    /// How often a nagging reminder alerted again since it ended.
+    #[serde(default)]
+    nag_count: usize,
+    /// Lowercase and without the leading `#`, e.g. `work`.
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Priority,
+    /// The single steps of the reminder, unchecked again whenever it restarts.
+    #[serde(default)]
+    checklist: Vec<ChecklistItem>,
+    /// The id of the reminder, that restarts once this one ended.
+    #[serde(default)]
+    next_in_chain: Option<usize>,
+    /// Waits for the previous reminder of its chain to end, its end time is pushed back until then.
+    #[serde(default)]
+    waiting: bool,
+    /// Turns the reminder into a pomodoro session, every phase restarts it with the length of the next one.
+    #[serde(default)]
+    pomodoro: Option<PomodoroSession>,
 }
 
 impl Reminder {
@@ -100,20 +184,44 @@ impl Reminder {
             restart_flag: false,
             paused: false,
             repeating: false,
+            recurrence: Recurrence::default(),
             send_e_message: true,
+            owner: None,
+            snooze_duration: None,
+            snooze_count: 0,
+            pause_count: 0,
+            warnings: vec![],
+            fired_warnings: vec![],
+            warning_needs_confirmation: None,
+            nag: false,
+            delivered_to: vec![],
+            last_alert: None,
+            nag_count: 0,
+            tags: vec![],
+            priority: Priority::Normal,
+            checklist: vec![],
+            next_in_chain: None,
+            waiting: false,
+            pomodoro: None,
         }
     }
-    #[must_use]
-    pub fn from_api_reminder(id: usize, value: ApiReminder) -> Self {
+    /// Builds a new reminder from what a client sent.
+    /// A time of day, that already passed today, rolls over to tomorrow.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_api_reminder(id: usize, value: ApiReminder) -> Result<Self, ApiError> {
+        if value.name.trim().is_empty() {
+            return Err(ApiError::invalid_input("A reminder needs a name"));
+        }
         let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
-        Self {
+        let finish_time = checked_finish_time(&value.reminder_type, value.finish_time, now)?;
+        Ok(Self {
             id,
             name: value.name,
             description: value.description,
             start_time: now,
             #[allow(clippy::arithmetic_side_effects)]
-            whole_duration: value.finish_time - now,
-            finish_time: value.finish_time,
+            whole_duration: finish_time - now,
+            finish_time,
             needs_confirmation: false,
             already_confirmed: false,
             delete_flag: false,
@@ -121,13 +229,66 @@ impl Reminder {
             reminder_type: value.reminder_type,
             paused: false,
             repeating: false,
+            recurrence: Recurrence::default(),
             send_e_message: true,
+            owner: None,
+            snooze_duration: None,
+            snooze_count: 0,
+            pause_count: 0,
+            warnings: vec![],
+            fired_warnings: vec![],
+            warning_needs_confirmation: None,
+            nag: false,
+            delivered_to: vec![],
+            last_alert: None,
+            nag_count: 0,
+            tags: parse_tags(value.tags.join(" ").as_str()),
+            priority: value.priority,
+            checklist: value
+                .checklist
+                .into_iter()
+                .filter(|text| !text.trim().is_empty())
+                .map(ChecklistItem::new)
+                .collect(),
+            next_in_chain: None,
+            waiting: false,
+            pomodoro: None,
+        })
+    }
+    /// Moves the reminder to a new end, following the same rules as `from_api_reminder`.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retime(&mut self, time_object: TimeObject) -> Result<(), ApiError> {
+        if let Some(recurrence) = &time_object.recurrence {
+            check_recurrence(recurrence)?;
+        }
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        let finish_time =
+            checked_finish_time(&time_object.reminder_type, time_object.finish_time, now)?;
+        self.whole_duration = if time_object.reminder_type == ReminderType::Time {
+            #[allow(clippy::arithmetic_side_effects)]
+            {
+                finish_time - now
+            }
+        } else {
+            time_object.duration
+        };
+        self.set_reminder_type(time_object.reminder_type);
+        // A new end is a new occurrence, that alerts and needs its own confirmation
+        self.set_finish_time(finish_time);
+        self.needs_confirmation = false;
+        self.warning_needs_confirmation = None;
+        if let Some(recurrence) = time_object.recurrence {
+            self.set_recurrence(recurrence);
         }
+        Ok(())
     }
     #[must_use]
     pub fn id(&self) -> usize {
         self.id
     }
+    pub fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
     #[must_use]
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -142,10 +303,22 @@ impl Reminder {
     pub fn set_description(&mut self, description: String) {
         self.description = description;
     }
+    #[must_use]
+    pub fn reminder_type(&self) -> &ReminderType {
+        &self.reminder_type
+    }
     pub fn set_reminder_type(&mut self, reminder_type: ReminderType) {
+        if self.is_note() && reminder_type != ReminderType::Note {
+            // A note becomes a timed reminder, so its progress starts now
+            self.start_time = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        }
         self.reminder_type = reminder_type;
     }
     #[must_use]
+    pub fn is_note(&self) -> bool {
+        self.reminder_type == ReminderType::Note
+    }
+    #[must_use]
     pub fn send_e_message(&self) -> bool {
         self.send_e_message
     }
@@ -153,6 +326,199 @@ impl Reminder {
         self.send_e_message = !self.send_e_message;
     }
     #[must_use]
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+    pub fn set_owner(&mut self, owner: Option<String>) {
+        self.owner = owner;
+    }
+    #[must_use]
+    pub fn snooze_duration(&self) -> Option<Duration> {
+        self.snooze_duration
+    }
+    pub fn set_snooze_duration(&mut self, snooze_duration: Option<Duration>) {
+        self.snooze_duration = snooze_duration;
+    }
+    #[must_use]
+    pub fn snooze_count(&self) -> usize {
+        self.snooze_count
+    }
+    #[must_use]
+    pub fn pause_count(&self) -> usize {
+        self.pause_count
+    }
+    #[must_use]
+    pub fn warnings(&self) -> &[Duration] {
+        &self.warnings
+    }
+    /// Sets the lead times of the heads-ups, the longest first.
+    pub fn set_warnings(&mut self, mut warnings: Vec<Duration>) {
+        warnings.retain(|lead| lead.is_positive());
+        warnings.sort_unstable_by(|a, b| b.cmp(a));
+        warnings.dedup();
+        self.fired_warnings.retain(|lead| warnings.contains(lead));
+        self.warnings = warnings;
+    }
+    #[must_use]
+    pub fn warning_needs_confirmation(&self) -> Option<Duration> {
+        self.warning_needs_confirmation
+    }
+    #[must_use]
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// Replaces the tags, `#Work` and `work` are the same tag.
+    pub fn set_tags(&mut self, tags: &[String]) {
+        self.tags = parse_tags(tags.join(" ").as_str());
+    }
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+    #[must_use]
+    pub fn checklist(&self) -> &[ChecklistItem] {
+        &self.checklist
+    }
+    pub fn add_checklist_item(&mut self, text: String) {
+        self.checklist.push(ChecklistItem::new(text));
+    }
+    /// Checks or unchecks the item at `index`, returns if it is done now.
+    pub fn toggle_checklist_item(&mut self, index: usize) -> Option<bool> {
+        let item = self.checklist.get_mut(index)?;
+        item.toggle();
+        Some(item.done())
+    }
+    pub fn remove_checklist_item(&mut self, index: usize) -> Option<ChecklistItem> {
+        (index < self.checklist.len()).then(|| self.checklist.remove(index))
+    }
+    /// How many items are done and how many there are, `None` without a checklist.
+    #[must_use]
+    pub fn checklist_progress(&self) -> Option<(usize, usize)> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+        Some((
+            self.checklist.iter().filter(|item| item.done()).count(),
+            self.checklist.len(),
+        ))
+    }
+    #[must_use]
+    pub fn next_in_chain(&self) -> Option<usize> {
+        self.next_in_chain
+    }
+    pub fn set_next_in_chain(&mut self, next_in_chain: Option<usize>) {
+        self.next_in_chain = next_in_chain;
+    }
+    #[must_use]
+    pub fn waiting(&self) -> bool {
+        self.waiting
+    }
+    /// Rewinds the reminder and holds it, until the previous reminder of its chain ended.
+    /// An unacknowledged end of its last run is dropped, so it alerts again.
+    pub fn wait_in_chain(&mut self) {
+        self.needs_confirmation = false;
+        self.restart();
+        self.waiting = true;
+    }
+    /// Starts the reminder, because the previous reminder of its chain ended.
+    pub fn continue_chain(&mut self, events: &mut EventLog) {
+        self.needs_confirmation = false;
+        self.restart();
+        events.push(PastEvent::ReminderChainContinued(self.clone()));
+    }
+    #[must_use]
+    pub fn pomodoro(&self) -> Option<&PomodoroSession> {
+        self.pomodoro.as_ref()
+    }
+    /// Runs `session` on this reminder, starting with its first work phase.
+    pub fn start_pomodoro(&mut self, session: PomodoroSession) {
+        self.reminder_type = ReminderType::Duration;
+        self.whole_duration = session.phase_duration();
+        self.repeating = true;
+        self.recurrence = Recurrence::Restart;
+        self.pomodoro = Some(session);
+        self.restart();
+    }
+    /// If the reminder has `tag`, with or without the leading `#`.
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        normalize_tag(tag).is_some_and(|tag| self.tags.contains(&tag))
+    }
+    #[must_use]
+    pub fn nag(&self) -> bool {
+        self.nag
+    }
+    pub fn toggle_nag(&mut self) {
+        self.nag = !self.nag;
+    }
+    /// If the client with `client_id` still has to alert the user about this reminder.
+    #[must_use]
+    pub fn alert_pending(&self, client_id: &str) -> bool {
+        (self.needs_confirmation || self.warning_needs_confirmation.is_some())
+            && !self.delivered_to.iter().any(|id| id == client_id)
+    }
+    #[must_use]
+    pub fn delivered_to(&self) -> &[String] {
+        &self.delivered_to
+    }
+    /// The client with `client_id` alerted the user, the others still alert until someone acknowledges it.
+    pub fn mark_delivered(&mut self, client_id: &str) {
+        if !self.delivered_to.iter().any(|id| id == client_id) {
+            self.delivered_to.push(client_id.to_string());
+        }
+    }
+    #[must_use]
+    pub fn nag_count(&self) -> usize {
+        self.nag_count
+    }
+    /// Asks the clients to alert again, if this reminder nags and `interval` passed since the last alert.
+    /// Returns if it did.
+    pub fn nag_again(&mut self, interval: Duration, events: &mut EventLog) -> bool {
+        if !self.nag || !self.needs_confirmation {
+            return false;
+        }
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        if self
+            .last_alert
+            .is_some_and(|last_alert| last_alert.saturating_add(interval) > now)
+        {
+            return false;
+        }
+        self.delivered_to.clear();
+        self.last_alert = Some(now);
+        self.nag_count = self.nag_count.saturating_add(1);
+        events.push(PastEvent::ReminderNagged(self.clone()));
+        true
+    }
+    /// Fires a heads-up once the remaining time drops below one of the lead times.
+    /// Each lead time fires once per finish time, lead times passed together fire as one heads-up.
+    pub fn request_warning(&mut self, events: &mut EventLog) {
+        if self.is_note() || self.paused {
+            return;
+        }
+        let Some(remaining) = self.remaining_duration() else {
+            return;
+        };
+        // lead times lying ahead again after a restart, snooze or retime can fire again
+        self.fired_warnings.retain(|lead| *lead >= remaining);
+        let passed: Vec<Duration> = self
+            .warnings
+            .iter()
+            .filter(|lead| **lead >= remaining && !self.fired_warnings.contains(lead))
+            .copied()
+            .collect();
+        let Some(shortest) = passed.iter().min().copied() else {
+            return;
+        };
+        self.fired_warnings.extend(passed);
+        self.warning_needs_confirmation = Some(shortest);
+        self.delivered_to.clear();
+        events.push(PastEvent::ReminderWarning(self.clone()));
+    }
+    #[must_use]
     #[allow(clippy::arithmetic_side_effects)]
     pub fn remaining_duration(&self) -> Option<Duration> {
         let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
@@ -187,6 +553,10 @@ impl Reminder {
             100
         }
     }
+    #[must_use]
+    pub fn whole_duration(&self) -> Duration {
+        self.whole_duration
+    }
     pub fn set_whole_duration(&mut self, whole_duration: Duration) {
         self.whole_duration = whole_duration;
         self.already_confirmed = false;
@@ -207,16 +577,24 @@ impl Reminder {
     pub fn needs_confirmation(&self) -> bool {
         self.needs_confirmation
     }
+    /// Acknowledges the end or the heads-up of this reminder, which stops every client from alerting.
     pub fn confirm_finish_event(&mut self) {
         self.needs_confirmation = false;
+        self.warning_needs_confirmation = None;
         if self.remaining_duration().is_none() {
             self.already_confirmed = true;
         }
     }
-    pub fn request_confirmation(&mut self, last_event: &mut PastEvent) {
-        if !self.already_confirmed {
-            *last_event = PastEvent::ReminderEnded(self.clone());
+    /// Asks every client to alert about the end of this reminder.
+    /// Each occurrence of a repeating reminder alerts again, even if the last one was never acknowledged.
+    pub fn request_confirmation(&mut self, events: &mut EventLog) {
+        if !self.already_confirmed && (!self.needs_confirmation || self.repeating) {
+            events.push(PastEvent::ReminderEnded(self.clone()));
             self.needs_confirmation = true;
+            self.warning_needs_confirmation = None;
+            self.delivered_to.clear();
+            self.last_alert = Some(OffsetDateTime::now_utc().to_offset(my_local_offset()));
+            self.nag_count = 0;
         }
     }
     #[must_use]
@@ -241,6 +619,14 @@ impl Reminder {
     pub fn repeating(&self) -> bool {
         self.repeating
     }
+    #[must_use]
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+    pub fn set_recurrence(&mut self, recurrence: Recurrence) {
+        self.recurrence = recurrence;
+        self.repeating = true;
+    }
     pub fn toggle_repeat(&mut self) -> Option<bool> {
         let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
         #[allow(clippy::arithmetic_side_effects)]
@@ -253,12 +639,15 @@ impl Reminder {
     }
     #[allow(clippy::arithmetic_side_effects)]
     pub fn push_back_end_time_if_paused(&mut self, push_back_amount: Duration) {
-        if self.paused {
+        if self.paused || self.waiting {
             self.finish_time += push_back_amount;
         }
     }
     pub fn toggle_pause(&mut self) {
         self.paused = !self.paused;
+        if self.paused {
+            self.pause_count = self.pause_count.saturating_add(1);
+        }
     }
     pub fn restart(&mut self) {
         let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
@@ -266,30 +655,83 @@ impl Reminder {
         #[allow(clippy::arithmetic_side_effects)]
         match self.reminder_type {
             ReminderType::Time => {
-                self.finish_time = self.finish_time.replace_date(now.date());
-                while self.finish_time < now {
-                    self.finish_time += Duration::days(1);
-                }
+                self.finish_time = next_time_of_day(self.finish_time, now);
                 self.whole_duration = self.finish_time - now;
             }
             ReminderType::Duration => {
                 self.finish_time = now + self.whole_duration;
             }
-            ReminderType::Date => {}
+            ReminderType::Date | ReminderType::Note => {}
+        }
+        self.delete_flag = false;
+        self.restart_flag = false;
+        self.already_confirmed = false;
+        self.waiting = false;
+        self.snooze_count = 0;
+        self.pause_count = 0;
+        self.checklist.iter_mut().for_each(ChecklistItem::uncheck);
+    }
+    /// Moves a repeating reminder to its next occurrence according to its `Recurrence`.
+    /// A pomodoro session moves on to its next phase instead and stops repeating once it is over.
+    pub fn recur(&mut self) {
+        if self.is_note() {
+            return;
+        }
+        if let Some(pomodoro) = &mut self.pomodoro {
+            if let Some(phase_duration) = pomodoro.advance() {
+                self.whole_duration = phase_duration;
+                self.restart();
+            } else {
+                self.repeating = false;
+            }
+            return;
+        }
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        // A date has no duration to restart with, so without a rule it comes back next year
+        let recurrence = match (&self.recurrence, &self.reminder_type) {
+            (Recurrence::Restart, ReminderType::Date) => &Recurrence::Yearly,
+            (recurrence, _) => recurrence,
+        };
+        let Some(next_finish_time) = recurrence.next_finish_time(self.finish_time, now) else {
+            // A rule without a next occurrence is dropped, instead of being evaluated on every tick.
+            self.recurrence = Recurrence::Restart;
+            if self.reminder_type == ReminderType::Date {
+                // Restarting would leave the date in the past, so it would fire again on every tick
+                self.repeating = false;
+            }
+            self.restart();
+            return;
+        };
+        self.start_time = now;
+        self.finish_time = next_finish_time;
+        #[allow(clippy::arithmetic_side_effects)]
+        {
+            self.whole_duration = next_finish_time - now;
         }
         self.delete_flag = false;
         self.restart_flag = false;
         self.already_confirmed = false;
+        self.snooze_count = 0;
+        self.pause_count = 0;
+        self.checklist.iter_mut().for_each(ChecklistItem::uncheck);
     }
-    pub fn snooze(&mut self) {
+    /// Pushes the reminder back by `duration`, counted from now if it is already due.
+    pub fn snooze(&mut self, duration: Duration) {
+        if self.is_note() {
+            return;
+        }
         let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
         #[allow(clippy::arithmetic_side_effects)]
         if self.finish_time < now {
-            self.finish_time += Duration::minutes(5);
-            self.start_time += Duration::minutes(5);
-            self.needs_confirmation = false;
-            self.already_confirmed = false;
+            self.start_time = now;
+            self.finish_time = now + duration;
+        } else {
+            self.finish_time += duration;
         }
+        self.needs_confirmation = false;
+        self.warning_needs_confirmation = None;
+        self.already_confirmed = false;
+        self.snooze_count = self.snooze_count.saturating_add(1);
     }
     #[must_use]
     pub fn from_file(filename: &str) -> Option<Vec<Reminder>> {
@@ -313,16 +755,27 @@ impl Display for Reminder {
         let Ok(time_format) = format_description::parse("[hour]:[minute]:[second]") else {
             return Err(std::fmt::Error);
         };
-        let Ok(finish_time) = self.finish_time.format(&time_format) else {
+        let Ok(finish_time) = to_local(self.finish_time).format(&time_format) else {
             return Err(std::fmt::Error);
         };
         let Ok(date_format) = format_description::parse("[day].[month].[year]") else {
             return Err(std::fmt::Error);
         };
-        let Ok(finish_date) = self.finish_time.format(&date_format) else {
+        let Ok(finish_date) = to_local(self.finish_time).format(&date_format) else {
             return Err(std::fmt::Error);
         };
-        if time_left.is_positive() {
+        let checklist = self
+            .checklist_progress()
+            .map_or_else(String::new, |(done, total)| format!(" {done}/{total}"));
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| format!(" #{tag}"))
+            .collect::<Vec<String>>()
+            .concat();
+        if self.is_note() {
+            write!(f, "{}✎ note{}{}", self.name.clone(), checklist, tags)
+        } else if time_left.is_positive() {
             let mut progressbar = String::new();
             for _ in 0..(self.remaining_percent() / 5) {
                 progressbar.push('=');
@@ -330,12 +783,14 @@ impl Display for Reminder {
             progressbar.push('>');
             write!(
                 f,
-                "{}{} {} {}",
+                "{}{} {} {}{}{}",
                 self.name.clone(),
                 if self.restart_flag() {
                     "↻"
                 } else if self.delete_flag() {
                     "✗"
+                } else if self.waiting {
+                    "…"
                 } else if self.repeating() {
                     "∞"
                 } else {
@@ -358,11 +813,13 @@ impl Display for Reminder {
                 } else {
                     format!(" {finish_time} ")
                 },
+                checklist,
+                tags,
             )
         } else {
             write!(
                 f,
-                "{}{} {} {}  ",
+                "{}{} {} {}  {}{}",
                 self.name.clone(),
                 if self.repeating() {
                     "∞"
@@ -375,11 +832,24 @@ impl Display for Reminder {
                 },
                 finish_date,
                 finish_time,
+                checklist,
+                tags,
             )
         }
     }
 }
 #[cfg(feature = "colored")]
+impl Reminder {
+    /// The name, dimmed for low and highlighted for high priority reminders.
+    fn colored_name(&self) -> colored::ColoredString {
+        match self.priority {
+            Priority::Low => self.name.clone().bright_black(),
+            Priority::Normal => self.name.clone().green(),
+            Priority::High => self.name.clone().bright_yellow().bold(),
+        }
+    }
+}
+#[cfg(feature = "colored")]
 impl Display for Reminder {
     #[allow(clippy::arithmetic_side_effects, clippy::too_many_lines)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -388,16 +858,39 @@ impl Display for Reminder {
         let Ok(time_format) = format_description::parse("[hour]:[minute]:[second]") else {
             return Err(std::fmt::Error);
         };
-        let Ok(finish_time) = self.finish_time.format(&time_format) else {
+        let Ok(finish_time) = to_local(self.finish_time).format(&time_format) else {
             return Err(std::fmt::Error);
         };
         let Ok(date_format) = format_description::parse("[day].[month].[year]") else {
             return Err(std::fmt::Error);
         };
-        let Ok(finish_date) = self.finish_time.format(&date_format) else {
+        let Ok(finish_date) = to_local(self.finish_time).format(&date_format) else {
             return Err(std::fmt::Error);
         };
-        if time_left.is_positive() {
+        let checklist = match self.checklist_progress() {
+            Some((done, total)) if done == total => format!(" {done}/{total}").bright_green(),
+            Some((done, total)) => format!(" {done}/{total}").yellow(),
+            None => String::new().normal(),
+        };
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| format!(" {}", format!("#{tag}").color(tag_color(tag))))
+            .collect::<Vec<String>>()
+            .concat();
+        if self.is_note() {
+            write!(
+                f,
+                "{:>10}{}          {}{:<21}{}{}{}",
+                self.name.clone().cyan(),
+                "✎".cyan(),
+                "[".bright_green(),
+                "========NOTE=========".cyan(),
+                "]".bright_green(),
+                checklist,
+                tags,
+            )
+        } else if time_left.is_positive() {
             let mut progressbar = String::new();
             for _ in 0..(self.remaining_percent() / 5) {
                 progressbar.push('=');
@@ -405,16 +898,18 @@ impl Display for Reminder {
             progressbar.push('>');
             write!(
                 f,
-                "{:>10}{} {} {}{:<21}{} {} {}",
-                if self.repeating() {
+                "{:>10}{} {} {}{:<21}{}{} {} {}{}",
+                if self.repeating() && self.priority == Priority::Normal {
                     self.name.clone().green().clear()
                 } else {
-                    self.name.clone().green()
+                    self.colored_name()
                 },
                 if self.restart_flag() {
                     "↻".bright_blue()
                 } else if self.delete_flag() {
                     "✗".bright_red()
+                } else if self.waiting {
+                    "…".blue()
                 } else if self.repeating() {
                     "∞".blue()
                 } else {
@@ -443,12 +938,13 @@ impl Display for Reminder {
                     )
                 },
                 "[".bright_green(),
-                if self.paused {
+                if self.paused || self.waiting {
                     progressbar.blue()
                 } else {
                     progressbar.bright_red()
                 },
                 "]".bright_green(),
+                checklist,
                 if time_left.whole_days() > 0 {
                     finish_date.bright_red()
                 } else {
@@ -459,12 +955,13 @@ impl Display for Reminder {
                 } else {
                     " ".blue()
                 },
+                tags,
             )
         } else {
             write!(
                 f,
-                "{:>10}{}          {}{:<21}{} {} {}  ",
-                self.name.clone().green(),
+                "{:>10}{}          {}{:<21}{}{} {} {}  {}",
+                self.colored_name(),
                 if self.repeating() {
                     "∞".blue()
                 } else if self.delete_flag() {
@@ -477,8 +974,10 @@ impl Display for Reminder {
                 "[".bright_green(),
                 "========DONE=========".yellow(),
                 "]".bright_green(),
+                checklist,
                 finish_date.bright_red(),
                 finish_time.bright_red(),
+                tags,
             )
         }
     }
@@ -500,7 +999,25 @@ impl Default for Reminder {
             restart_flag: false,
             paused: false,
             repeating: false,
+            recurrence: Recurrence::default(),
             send_e_message: true,
+            owner: None,
+            snooze_duration: None,
+            snooze_count: 0,
+            pause_count: 0,
+            warnings: vec![],
+            fired_warnings: vec![],
+            warning_needs_confirmation: None,
+            nag: false,
+            delivered_to: vec![],
+            last_alert: None,
+            nag_count: 0,
+            tags: vec![],
+            priority: Priority::Normal,
+            checklist: vec![],
+            next_in_chain: None,
+            waiting: false,
+            pomodoro: None,
         }
     }
 }
@@ -512,4 +1029,131 @@ pub struct ApiReminder {
     pub description: String,
     pub finish_time: OffsetDateTime,
     pub reminder_type: ReminderType,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// The texts of the checklist items, all of them unchecked.
+    #[serde(default)]
+    pub checklist: Vec<String>,
+}
+
+/// The body of every `/reminders/batch/..` request.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub ids: Vec<usize>,
+    /// Needed by `push_duration` and `cut_duration`, optional for `snooze`.
+    #[serde(default)]
+    pub duration: Option<core::time::Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration, OffsetDateTime};
+
+    use super::{ReminderType, TimeObject};
+    use crate::{api_error::ApiError, past_event::EventLog, test_reminders::reminder};
+
+    fn time_object(reminder_type: ReminderType, finish_time: OffsetDateTime) -> TimeObject {
+        TimeObject {
+            reminder_type,
+            finish_time,
+            duration: Duration::hours(1),
+            recurrence: None,
+        }
+    }
+
+    #[test]
+    fn retiming_a_confirmed_reminder_makes_it_alert_again() -> Result<(), ApiError> {
+        let now = OffsetDateTime::now_utc();
+        let mut reminder = reminder(1, "retimed")?;
+        reminder.set_finish_time(now.saturating_sub(Duration::minutes(1)));
+        reminder.request_confirmation(&mut EventLog::default());
+        reminder.confirm_finish_event();
+        assert!(reminder.already_confirmed());
+
+        reminder.request_confirmation(&mut EventLog::default());
+        assert!(!reminder.needs_confirmation());
+        reminder.retime(time_object(
+            ReminderType::Duration,
+            now.saturating_add(Duration::hours(1)),
+        ))?;
+        assert!(!reminder.already_confirmed());
+        assert!(!reminder.needs_confirmation());
+
+        reminder.retime(time_object(
+            ReminderType::Date,
+            now.saturating_sub(Duration::minutes(1)),
+        ))?;
+        let mut events = EventLog::default();
+        reminder.request_confirmation(&mut events);
+        assert!(reminder.needs_confirmation());
+        assert_eq!(events.last_id(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn retiming_moves_a_due_reminder_out_of_its_alert() -> Result<(), ApiError> {
+        let now = OffsetDateTime::now_utc();
+        let mut reminder = reminder(1, "retimed")?;
+        reminder.set_finish_time(now.saturating_sub(Duration::minutes(1)));
+        reminder.request_confirmation(&mut EventLog::default());
+        assert!(reminder.needs_confirmation());
+        reminder.retime(time_object(
+            ReminderType::Duration,
+            now.saturating_add(Duration::hours(1)),
+        ))?;
+        assert!(!reminder.needs_confirmation());
+        Ok(())
+    }
+
+    #[test]
+    fn retiming_a_note_starts_its_progress_now() -> Result<(), ApiError> {
+        let mut reminder = reminder(1, "note")?;
+        reminder.set_reminder_type(ReminderType::Note);
+        reminder.start_time = datetime!(2020-01-01 0:00 UTC);
+        let now = OffsetDateTime::now_utc();
+        reminder.retime(time_object(
+            ReminderType::Duration,
+            now.saturating_add(Duration::hours(1)),
+        ))?;
+        assert!(reminder.start_time >= now);
+        Ok(())
+    }
+
+    #[test]
+    fn checklist_progress_counts_the_done_items() -> Result<(), ApiError> {
+        let mut reminder = reminder(1, "checklist")?;
+        assert_eq!(reminder.checklist_progress(), None);
+        reminder.add_checklist_item("first".to_string());
+        reminder.add_checklist_item("second".to_string());
+        assert_eq!(reminder.checklist_progress(), Some((0, 2)));
+        assert_eq!(reminder.toggle_checklist_item(1), Some(true));
+        assert_eq!(reminder.checklist_progress(), Some((1, 2)));
+        assert_eq!(reminder.toggle_checklist_item(2), None);
+        assert_eq!(reminder.toggle_checklist_item(1), Some(false));
+        assert_eq!(reminder.checklist_progress(), Some((0, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn restarting_unchecks_the_checklist() -> Result<(), ApiError> {
+        let mut reminder = reminder(1, "checklist")?;
+        reminder.add_checklist_item("first".to_string());
+        reminder.toggle_checklist_item(0);
+        assert_eq!(reminder.checklist_progress(), Some((1, 1)));
+        reminder.restart();
+        assert_eq!(reminder.checklist_progress(), Some((0, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn removing_the_last_item_removes_the_progress() -> Result<(), ApiError> {
+        let mut reminder = reminder(1, "checklist")?;
+        reminder.add_checklist_item("only".to_string());
+        assert!(reminder.remove_checklist_item(1).is_none());
+        assert!(reminder.remove_checklist_item(0).is_some());
+        assert_eq!(reminder.checklist_progress(), None);
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{past_event::LoggedEvent, reminder::Reminder};
+
+/// A message on the servers `/stream` endpoint.
+/// Every connection starts with a `Snapshot`, followed by `Diff`s and `Event`s as they happen.
+#[allow(clippy::module_name_repetitions, clippy::large_enum_variant)]
+#[derive(Clone, Serialize, Deserialize)]
+pub enum StreamEvent {
+    Snapshot(Vec<Reminder>),
+    Diff {
+        changed: Vec<Reminder>,
+        removed: Vec<usize>,
+    },
+    Event(LoggedEvent),
+}
+
+impl StreamEvent {
+    /// Builds the `Diff` that turns `old` into `new`, or `None` if nothing changed.
+    #[must_use]
+    pub fn diff(old: &[Reminder], new: &[Reminder]) -> Option<Self> {
+        let changed: Vec<Reminder> = new
+            .iter()
+            .filter(|reminder| !old.contains(reminder))
+            .cloned()
+            .collect();
+        let removed: Vec<usize> = old
+            .iter()
+            .map(Reminder::id)
+            .filter(|id| !new.iter().any(|reminder| reminder.id() == *id))
+            .collect();
+        if changed.is_empty() && removed.is_empty() {
+            None
+        } else {
+            Some(StreamEvent::Diff { changed, removed })
+        }
+    }
+    /// Applies a `Snapshot` or `Diff` to a local list of reminders.
+    /// Returns the reminders that were added or changed.
+    pub fn apply(&self, reminders: &mut Vec<Reminder>) -> Vec<Reminder> {
+        match self {
+            StreamEvent::Snapshot(new_reminders) => {
+                reminders.clone_from(new_reminders);
+                new_reminders.clone()
+            }
+            StreamEvent::Diff { changed, removed } => {
+                reminders.retain(|reminder| !removed.contains(&reminder.id()));
+                for changed_reminder in changed {
+                    if let Some(reminder) = reminders
+                        .iter_mut()
+                        .find(|reminder| reminder.id() == changed_reminder.id())
+                    {
+                        *reminder = changed_reminder.clone();
+                    } else {
+                        reminders.push(changed_reminder.clone());
+                    }
+                }
+                changed.clone()
+            }
+            StreamEvent::Event(_) => vec![],
+        }
+    }
+}
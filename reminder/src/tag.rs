@@ -0,0 +1,61 @@
+use colored::Color;
+
+/// The colors tags are shown in, every tag always gets the same one.
+const TAG_COLORS: [Color; 8] = [
+    Color::Magenta,
+    Color::Yellow,
+    Color::Cyan,
+    Color::Blue,
+    Color::BrightMagenta,
+    Color::BrightYellow,
+    Color::BrightCyan,
+    Color::BrightBlue,
+];
+
+/// Turns `#Work`, `work` or ` #work ` into `work`. Returns `None` for something, that can not be a tag.
+#[must_use]
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    if tag.is_empty()
+        || tag.contains(|character: char| character.is_whitespace() || character == ',')
+    {
+        return None;
+    }
+    Some(tag)
+}
+
+/// Reads tags separated by spaces or commas, like `#work #home` or `work, home`.
+#[must_use]
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for tag in input
+        .split(|character: char| character.is_whitespace() || character == ',')
+        .filter_map(normalize_tag)
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Splits the `#tags` out of a reminder name, `buy milk #home` becomes `buy milk` and `home`.
+#[must_use]
+pub fn split_tags(input: &str) -> (String, Vec<String>) {
+    let (tags, name): (Vec<&str>, Vec<&str>) = input
+        .split_whitespace()
+        .partition(|word| word.len() > 1 && word.starts_with('#'));
+    (name.join(" "), parse_tags(tags.join(" ").as_str()))
+}
+
+/// The color `tag` is shown in.
+#[must_use]
+pub fn tag_color(tag: &str) -> Color {
+    let index = tag.bytes().fold(0_usize, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(usize::from(byte))
+    });
+    TAG_COLORS
+        .get(index.checked_rem(TAG_COLORS.len()).unwrap_or_default())
+        .copied()
+        .unwrap_or(Color::Magenta)
+}
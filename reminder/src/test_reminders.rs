@@ -0,0 +1,27 @@
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    api_error::ApiError,
+    priority::Priority,
+    reminder::{ApiReminder, Reminder, ReminderType},
+};
+
+/// A duration reminder, that ends in an hour.
+pub fn reminder(id: usize, name: &str) -> Result<Reminder, ApiError> {
+    Reminder::from_api_reminder(
+        id,
+        ApiReminder {
+            name: name.to_string(),
+            description: String::new(),
+            finish_time: OffsetDateTime::now_utc().saturating_add(Duration::hours(1)),
+            reminder_type: ReminderType::Duration,
+            tags: vec![],
+            priority: Priority::default(),
+            checklist: vec![],
+        },
+    )
+}
+
+pub fn names(reminders: &[Reminder]) -> Vec<&str> {
+    reminders.iter().map(Reminder::name).collect()
+}
@@ -0,0 +1,328 @@
+use duration_string::DurationString;
+use time::{Date, Duration, Month, OffsetDateTime, Time, Weekday};
+
+use crate::{
+    recurrence::parse_weekday,
+    reminder::{ReminderType, TimeObject},
+    timezone::at_local_time,
+};
+
+/// Every kind of input `parse_time_input` understands, for prompts and error messages.
+pub const TIME_INPUT_EXAMPLES: &str =
+    "1h10m | in 3 days | 15:23 | 9pm | 8.11.2023 15:23 | tomorrow 9:00 | next monday | friday 17:00";
+
+/// A day, that a time input can start with.
+enum Day {
+    Today,
+    Tomorrow,
+    Weekday(Weekday),
+    NextWeekday(Weekday),
+    Date(Date),
+}
+
+/// Parses the end of a reminder, as users type it, relative to `now`.
+/// Durations (`1h10m`, `in 3 days`) become `Duration` reminders,
+/// a bare time of day (`15:23`, `9:30pm`) becomes a `Time` reminder
+/// and everything naming a day (`8.11.2023`, `tomorrow 9:00`, `next monday`, `friday 17:00`) becomes a `Date` reminder.
+/// A day without a time keeps the current time of day.
+#[allow(clippy::result_unit_err, clippy::missing_errors_doc)]
+pub fn parse_time_input(input: &str, now: OffsetDateTime) -> Result<TimeObject, ()> {
+    let input = input.trim().to_lowercase();
+    if let Some(duration) = parse_duration(input.strip_prefix("in ").unwrap_or(&input)) {
+        return Ok(TimeObject {
+            reminder_type: ReminderType::Duration,
+            finish_time: now.checked_add(duration).ok_or(())?,
+            duration,
+            recurrence: None,
+        });
+    }
+    let mut words = input
+        .split_whitespace()
+        .filter(|word| *word != "at" && *word != "on")
+        .peekable();
+    let day = match words.peek().copied() {
+        Some("today") => Some(Day::Today),
+        Some("tomorrow") => Some(Day::Tomorrow),
+        Some("next") => {
+            words.next();
+            Some(Day::NextWeekday(
+                words.peek().copied().and_then(parse_weekday).ok_or(())?,
+            ))
+        }
+        Some(word) => parse_weekday(word)
+            .map(Day::Weekday)
+            .or_else(|| parse_date(word, now).map(Day::Date)),
+        None => return Err(()),
+    };
+    if day.is_some() {
+        words.next();
+    }
+    let time_input: String = words.collect();
+    let time = if time_input.is_empty() {
+        if day.is_none() {
+            return Err(());
+        }
+        now.time()
+    } else {
+        parse_time_of_day(time_input.as_str()).ok_or(())?
+    };
+    let Some(day) = day else {
+        let finish_time = at_local_time(now.date(), time);
+        #[allow(clippy::arithmetic_side_effects)]
+        return Ok(TimeObject {
+            reminder_type: ReminderType::Time,
+            finish_time,
+            duration: finish_time - now,
+            recurrence: None,
+        });
+    };
+    let today = now.date();
+    let date = match day {
+        Day::Today => today,
+        Day::Tomorrow => today.next_day().ok_or(())?,
+        Day::Date(date) => date,
+        Day::Weekday(weekday) => {
+            let date = upcoming(today, weekday, 0).ok_or(())?;
+            if at_local_time(date, time) > now {
+                date
+            } else {
+                upcoming(today, weekday, 1).ok_or(())?
+            }
+        }
+        Day::NextWeekday(weekday) => upcoming(today, weekday, 1).ok_or(())?,
+    };
+    let finish_time = at_local_time(date, time);
+    #[allow(clippy::arithmetic_side_effects)]
+    Ok(TimeObject {
+        reminder_type: ReminderType::Date,
+        finish_time,
+        duration: finish_time - now,
+        recurrence: None,
+    })
+}
+
+/// Parses `1h10m15s`, `90s`, `2w` as well as spelled out units like `3 days` or `1 hour 30 minutes`.
+#[must_use]
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let compact: String = input
+        .split_whitespace()
+        .filter(|word| *word != "and")
+        .map(|word| match word {
+            "sec" | "secs" | "second" | "seconds" => "s",
+            "min" | "mins" | "minute" | "minutes" => "m",
+            "hour" | "hours" => "h",
+            "day" | "days" => "d",
+            "week" | "weeks" => "w",
+            word => word,
+        })
+        .collect();
+    let duration: core::time::Duration = DurationString::from_string(compact).ok()?.into();
+    duration.try_into().ok()
+}
+
+/// Formats a duration the way `parse_duration` reads it, e.g. `1h 15m`, leaving out the units that are zero.
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    #[allow(clippy::arithmetic_side_effects)]
+    let parts = [
+        (duration.whole_days(), "d"),
+        (duration.whole_hours() % 24, "h"),
+        (duration.whole_minutes() % 60, "m"),
+        (duration.whole_seconds() % 60, "s"),
+    ];
+    let formatted: Vec<String> = parts
+        .iter()
+        .filter(|(amount, _)| *amount != 0)
+        .map(|(amount, unit)| format!("{amount}{unit}"))
+        .collect();
+    if formatted.is_empty() {
+        return String::from("0s");
+    }
+    formatted.join(" ")
+}
+
+/// Parses `15:23`, `9`, `9am`, `9:30 pm` and `12am`.
+fn parse_time_of_day(input: &str) -> Option<Time> {
+    let input = input.replace(' ', "");
+    let (clock, afternoon) = if let Some(clock) = input.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (input.as_str(), None)
+    };
+    let (hour, minute): (u8, u8) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse().ok()?, minute.parse().ok()?),
+        None => (clock.parse().ok()?, 0),
+    };
+    let hour = match afternoon {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(true) => (hour % 12).checked_add(12)?,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    Time::from_hms(hour, minute, 0).ok()
+}
+
+/// Parses `8.11.2023`, or `8.11.` for the next 8th of november.
+fn parse_date(input: &str, now: OffsetDateTime) -> Option<Date> {
+    let mut parts = input.split('.');
+    let day: u8 = parts.next()?.parse().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    match parts.next() {
+        Some("") | None => {
+            let date = Date::from_calendar_date(now.year(), month, day).ok()?;
+            if date < now.date() {
+                Date::from_calendar_date(now.year().checked_add(1)?, month, day).ok()
+            } else {
+                Some(date)
+            }
+        }
+        Some(year) => Date::from_calendar_date(year.parse().ok()?, month, day).ok(),
+    }
+}
+
+/// The first `weekday` at least `min_days_ahead` days after `today`.
+fn upcoming(today: Date, weekday: Weekday, min_days_ahead: u8) -> Option<Date> {
+    let mut date = today;
+    for _ in 0..min_days_ahead {
+        date = date.next_day()?;
+    }
+    while date.weekday() != weekday {
+        date = date.next_day()?;
+    }
+    Some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{
+        macros::{date, datetime, time},
+        Duration, OffsetDateTime,
+    };
+
+    use super::{format_duration, parse_duration, parse_time_input, parse_time_of_day};
+    use crate::reminder::{ReminderType, TimeObject};
+
+    /// A monday morning in winter, the tests run in `Europe/Berlin`.
+    const NOW: OffsetDateTime = datetime!(2024-03-04 10:00 +1);
+
+    fn parsed(input: &str) -> Result<TimeObject, String> {
+        parse_time_input(input, NOW).map_err(|()| format!("'{input}' was rejected"))
+    }
+
+    #[test]
+    fn am_and_pm_follow_the_twelve_hour_clock() {
+        assert_eq!(parse_time_of_day("9am"), Some(time!(9:00)));
+        assert_eq!(parse_time_of_day("9:30 pm"), Some(time!(21:30)));
+        assert_eq!(parse_time_of_day("12am"), Some(time!(0:00)));
+        assert_eq!(parse_time_of_day("12:15am"), Some(time!(0:15)));
+        assert_eq!(parse_time_of_day("12pm"), Some(time!(12:00)));
+        assert_eq!(parse_time_of_day("11:59pm"), Some(time!(23:59)));
+        assert_eq!(parse_time_of_day("0am"), None);
+        assert_eq!(parse_time_of_day("13pm"), None);
+        assert_eq!(parse_time_of_day("15:23"), Some(time!(15:23)));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("9:60"), None);
+    }
+
+    #[test]
+    fn a_bare_time_of_day_is_a_time_reminder_today() -> Result<(), String> {
+        let time_object = parsed("9pm")?;
+        assert_eq!(time_object.reminder_type, ReminderType::Time);
+        assert_eq!(time_object.finish_time, datetime!(2024-03-04 21:00 +1));
+        assert_eq!(time_object.duration, Duration::hours(11));
+        Ok(())
+    }
+
+    #[test]
+    fn next_weekday_skips_today() -> Result<(), String> {
+        let time_object = parsed("next monday")?;
+        assert_eq!(time_object.reminder_type, ReminderType::Date);
+        assert_eq!(time_object.finish_time, datetime!(2024-03-11 10:00 +1));
+        // Without `next`, today only counts while the time is still ahead
+        assert_eq!(
+            parsed("monday")?.finish_time,
+            datetime!(2024-03-11 10:00 +1)
+        );
+        assert_eq!(
+            parsed("monday 11:00")?.finish_time,
+            datetime!(2024-03-04 11:00 +1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn weekday_with_a_time_is_a_date_reminder() -> Result<(), String> {
+        let time_object = parsed("friday 17:00")?;
+        assert_eq!(time_object.reminder_type, ReminderType::Date);
+        assert_eq!(time_object.finish_time, datetime!(2024-03-08 17:00 +1));
+        assert_eq!(
+            parsed("on fri at 5pm")?.finish_time,
+            datetime!(2024-03-08 17:00 +1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn spelled_out_durations_are_duration_reminders() -> Result<(), String> {
+        let time_object = parsed("in 3 days")?;
+        assert_eq!(time_object.reminder_type, ReminderType::Duration);
+        assert_eq!(time_object.duration, Duration::days(3));
+        assert_eq!(time_object.finish_time, datetime!(2024-03-07 10:00 +1));
+        assert_eq!(
+            parse_duration("1 hour and 30 minutes"),
+            Some(Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("1h10m"), Some(Duration::minutes(70)));
+        Ok(())
+    }
+
+    #[test]
+    fn dates_without_a_year_that_passed_are_next_year() -> Result<(), String> {
+        let december = datetime!(2024-12-01 10:00 +1);
+        let time_object =
+            parse_time_input("8.11.", december).map_err(|()| "'8.11.' was rejected")?;
+        assert_eq!(time_object.finish_time.date(), date!(2025 - 11 - 08));
+        assert_eq!(parsed("8.11.")?.finish_time, datetime!(2024-11-08 10:00 +1));
+        assert_eq!(
+            parsed("8.11.2023 15:23")?.finish_time,
+            datetime!(2023-11-08 15:23 +1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_inputs_are_errors() {
+        for input in [
+            "",
+            "   ",
+            "next",
+            "next blursday",
+            "25:00",
+            "tomorrow 9:60",
+            "32.1.",
+            "29.2.2023",
+            "1h1x",
+            "in",
+            "soon",
+            "99999999999999999999w",
+            "18446744073709551615s",
+            // Representable as a duration, but it would end after the last representable date
+            "in 9999999999999 weeks",
+        ] {
+            assert!(
+                parse_time_input(input, NOW).is_err(),
+                "'{input}' should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn formatted_durations_parse_back() {
+        let duration = Duration::seconds(90_061);
+        assert_eq!(format_duration(duration), "1d 1h 1m 1s");
+        assert_eq!(parse_duration(&format_duration(duration)), Some(duration));
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+    }
+}
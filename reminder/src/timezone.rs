@@ -0,0 +1,84 @@
+use std::sync::OnceLock;
+
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::{system, timezones, OffsetDateTimeExt, PrimitiveDateTimeExt, Tz};
+
+static TIMEZONE: OnceLock<&'static Tz> = OnceLock::new();
+
+/// Sets the timezone every local time is computed in, from the IANA name in `remindy.toml` (e.g. `Europe/Berlin`).
+/// Without a name the `TZ` environment variable and then the system timezone are used.
+/// Falls back to UTC and returns why, if none of them is a known timezone.
+#[allow(clippy::missing_errors_doc)]
+pub fn init_timezone(name: Option<&str>) -> Result<(), String> {
+    let (timezone, result) = match name {
+        Some(name) => match timezones::get_by_name(name) {
+            Some(timezone) => (timezone, Ok(())),
+            None => (
+                timezones::db::UTC,
+                Err(format!(
+                    "Unknown timezone '{name}' in remindy.toml, using UTC"
+                )),
+            ),
+        },
+        None => match system_timezone() {
+            Ok(timezone) => (timezone, Ok(())),
+            Err(error) => (timezones::db::UTC, Err(error)),
+        },
+    };
+    if TIMEZONE.set(timezone).is_err() {
+        return Err(String::from("The timezone was already set"));
+    }
+    result
+}
+
+fn system_timezone() -> Result<&'static Tz, String> {
+    if let Some(timezone) = std::env::var("TZ")
+        .ok()
+        .and_then(|name| timezones::get_by_name(name.trim_start_matches(':')))
+    {
+        return Ok(timezone);
+    }
+    system::get_timezone()
+        .map_err(|error| format!("Could not find the system timezone ({error}), using UTC"))
+}
+
+/// The configured timezone, or the system timezone if `init_timezone` was never called.
+#[must_use]
+pub fn local_timezone() -> &'static Tz {
+    TIMEZONE.get_or_init(default_timezone)
+}
+
+#[cfg(not(test))]
+fn default_timezone() -> &'static Tz {
+    system_timezone().unwrap_or(timezones::db::UTC)
+}
+
+/// Tests run in a timezone with DST changes, wherever they run.
+#[cfg(test)]
+fn default_timezone() -> &'static Tz {
+    timezones::db::europe::BERLIN
+}
+
+/// `date_time` as the wall clock in the local timezone shows it, with the offset valid at that moment.
+#[must_use]
+pub fn to_local(date_time: OffsetDateTime) -> OffsetDateTime {
+    date_time.to_timezone(local_timezone())
+}
+
+/// The moment the local wall clock shows `time` on `date`.
+/// Times skipped by a DST change are moved forward by the skipped hour,
+/// times repeated by one resolve to their first occurrence.
+#[must_use]
+pub fn at_local_time(date: Date, time: Time) -> OffsetDateTime {
+    let date_time = PrimitiveDateTime::new(date, time);
+    date_time
+        .assume_timezone(local_timezone())
+        .take_first()
+        .or_else(|| {
+            date_time
+                .checked_add(Duration::hours(1))?
+                .assume_timezone(local_timezone())
+                .take_first()
+        })
+        .unwrap_or_else(|| date_time.assume_timezone_utc(local_timezone()))
+}
@@ -1,407 +1,862 @@
-use crate::DBFile;
+use crate::{
+    auth::CurrentUser,
+    error::{json_body, lock, optional_json_body, reminder_by_id, ApiErrorResponse, ApiResult},
+    history::HistoryEntry,
+    DBFile, ReminderLibrary,
+};
 use axum::{
-    extract::{rejection::JsonRejection, Path, State},
+    extract::{rejection::JsonRejection, Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
+use config::Snooze;
+use json_store_rs::JsonStore;
 use reminder::{
-    get_reminder_by_id,
-    past_event::PastEvent,
-    reminder::{ApiReminder, Reminder, TimeObject},
+    api_error::{ApiError, ApiErrorCode},
+    chain::{linked_ids, unlink},
+    completion::{Completion, CompletionStats},
+    history::{HistoryPreview, Recording},
+    library::ReminderTemplate,
+    past_event::{LoggedEvent, PastEvent},
+    priority::Priority,
+    recurrence::Recurrence,
+    reminder::{check_recurrence, my_local_offset, ApiReminder, Reminder, TimeObject},
+    tag::normalize_tag,
+    time_input::{parse_time_input, TIME_INPUT_EXAMPLES},
+    timezone::to_local,
 };
+use serde::Deserialize;
 use std::sync::{Arc, Mutex};
+use time::OffsetDateTime;
 
-// TODO: move `PastEvent` into DBFile
-type ApiState = State<(Arc<Mutex<DBFile>>, Arc<Mutex<PastEvent>>)>;
+type ApiState = State<Arc<Mutex<DBFile>>>;
+type LibraryState = State<(Arc<Mutex<ReminderLibrary>>, Arc<Mutex<DBFile>>)>;
 
-pub async fn get_past_event(State((_, past_event)): ApiState) -> (StatusCode, Json<PastEvent>) {
-    if let Ok(past_event) = past_event.lock() {
-        (StatusCode::OK, Json(past_event.clone()))
-    } else {
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(PastEvent::None))
-    }
+pub async fn get_past_event(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Json<PastEvent>> {
+    let db_file = lock(&db_file)?;
+    Ok(Json(
+        db_file
+            .events
+            .since(0)
+            .into_iter()
+            .rev()
+            .find(|event| current_user.can_access(event.event().owner()))
+            .map_or(PastEvent::None, |event| event.event().clone()),
+    ))
 }
 
-pub async fn all_reminder(State((reminders, _)): ApiState) -> (StatusCode, Json<Vec<Reminder>>) {
-    if let Ok(reminders) = reminders.lock() {
-        (StatusCode::OK, Json(reminders.reminders.clone()))
-    } else {
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    #[serde(default)]
+    since: usize,
+}
+
+pub async fn get_events(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<EventsQuery>,
+) -> ApiResult<Json<Vec<LoggedEvent>>> {
+    let db_file = lock(&db_file)?;
+    let mut events = db_file.events.since(query.since);
+    events.retain(|event| current_user.can_access(event.event().owner()));
+    Ok(Json(events))
+}
+
+#[derive(Deserialize)]
+pub struct TagQuery {
+    /// Only reminders with this tag, with or without the leading `#`.
+    tag: Option<String>,
+}
+
+impl TagQuery {
+    fn matches(&self, reminder: &Reminder) -> bool {
+        self.tag
+            .as_deref()
+            .and_then(normalize_tag)
+            .is_none_or(|tag| reminder.has_tag(&tag))
     }
 }
 
+pub async fn all_reminder(
+    State(reminders): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<TagQuery>,
+) -> ApiResult<Json<Vec<Reminder>>> {
+    let reminders = lock(&reminders)?;
+    Ok(Json(
+        reminders
+            .reminders
+            .iter()
+            .filter(|reminder| current_user.can_access(reminder.owner()) && query.matches(reminder))
+            .cloned()
+            .collect(),
+    ))
+}
+
 pub async fn all_reminder_formatted(
-    State((db_file, _)): ApiState,
-) -> (StatusCode, Json<Vec<String>>) {
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<TagQuery>,
+) -> ApiResult<Json<Vec<String>>> {
     let mut result: Vec<String> = vec![];
-    if let Ok(db_file) = db_file.lock() {
-        for reminder in &db_file.reminders {
-            let time_left = reminder.remaining_duration();
-            let Ok(time_format) = time::format_description::parse("[hour]:[minute]:[second]")
-            else {
-                return (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]));
-            };
-            result.push(format!(
-                "\r{}n\r{}",
-                reminder,
-                if let Some(time_left) = time_left {
-                    if time_left.whole_days() > 0 {
-                        let Ok(finish_time) = reminder.finish_time().format(&time_format) else {
-                            return (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]));
-                        };
-                        format!(
-                            "                        {}\n\r{}",
-                            finish_time,
-                            reminder.description()
-                        )
-                    } else {
-                        reminder.description().replace('\n', "\n\r")
-                    }
+    let db_file = lock(&db_file)?;
+    for reminder in db_file
+        .reminders
+        .iter()
+        .filter(|reminder| current_user.can_access(reminder.owner()) && query.matches(reminder))
+    {
+        let time_left = reminder.remaining_duration();
+        let Ok(time_format) = time::format_description::parse("[hour]:[minute]:[second]") else {
+            return Err(ApiError::internal("Could not parse the time format").into());
+        };
+        result.push(format!(
+            "\r{}n\r{}",
+            reminder,
+            if let Some(time_left) = time_left {
+                if time_left.whole_days() > 0 {
+                    let Ok(finish_time) = to_local(reminder.finish_time()).format(&time_format)
+                    else {
+                        return Err(ApiError::internal("Could not format the finish time")
+                            .with_reminder_id(reminder.id())
+                            .into());
+                    };
+                    format!(
+                        "                        {}\n\r{}",
+                        finish_time,
+                        reminder.description()
+                    )
                 } else {
                     reminder.description().replace('\n', "\n\r")
                 }
-            ));
-        }
-        (StatusCode::OK, Json(result))
-    } else {
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
+            } else {
+                reminder.description().replace('\n', "\n\r")
+            }
+        ));
     }
+    Ok(Json(result))
 }
 
 pub async fn add_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
     api_reminder: Result<Json<ApiReminder>, JsonRejection>,
-) -> StatusCode {
-    // TODO: create fancy middleware, that does this boilerplate stuff
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(api_reminder)) = api_reminder else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    let max_id = db_file
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let api_reminder = json_body(api_reminder)?;
+    let new_id = next_reminder_id(&mut db_file);
+    let recording = Recording::start(&db_file.reminders, &[new_id]);
+    let mut new_reminder = Reminder::from_api_reminder(new_id, api_reminder)?;
+    new_reminder.set_owner(current_user.name());
+    db_file.reminders.push(new_reminder.clone());
+    db_file
+        .events
+        .push(PastEvent::ReminderCreated(new_reminder.clone()));
+    print!("\nn ({}) ", new_reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Hands out a new id, that no reminder ever had, so the completion history of a deleted reminder
+/// never mixes with a new one. Older databases without `last_reminder_id` continue after the highest known id.
+pub fn next_reminder_id(db_file: &mut DBFile) -> usize {
+    let highest_id = db_file
         .reminders
         .iter()
         .map(Reminder::id)
-        .max()
-        .unwrap_or_default();
-
-    let new_id = max_id.saturating_add(1);
-
-    let new_reminder = Reminder::from_api_reminder(new_id, api_reminder);
-    db_file.reminders.push(new_reminder.clone());
-    *past_event = PastEvent::ReminderCreated(new_reminder.clone());
-    print!("\nn ({}) ", new_reminder.name());
-    StatusCode::OK
+        .chain(
+            db_file
+                .completions
+                .completions()
+                .iter()
+                .map(Completion::reminder_id),
+        )
+        .fold(db_file.last_reminder_id, usize::max);
+    db_file.last_reminder_id = highest_id.saturating_add(1);
+    db_file.last_reminder_id
 }
 
 pub async fn restart_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        if reminder.restart_flag() {
-            *past_event = PastEvent::ReminderEdited(reminder.clone());
-            reminder.restart();
-        } else {
-            reminder.set_restart_flag(true);
-        }
-        print!("\nrs ({})", reminder.name());
-        StatusCode::OK
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    if reminder.restart_flag() {
+        db_file
+            .events
+            .push(PastEvent::ReminderEdited(reminder.clone()));
+        reminder.restart();
     } else {
-        StatusCode::NOT_FOUND
+        reminder.set_restart_flag(true);
     }
+    print!("\nrs ({})", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn force_restart_reminder(
-    State((db_file, _)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.restart();
-        print!("\nfrs ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.restart();
+    print!("\nfrs ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn rename_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
     name: Result<Json<String>, JsonRejection>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(name)) = name else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.set_name(name.clone());
-        *past_event = PastEvent::ReminderEdited(reminder.clone());
-        print!("\nrn ({name}) ");
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let name = json_body(name)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.set_name(name.clone());
+    db_file
+        .events
+        .push(PastEvent::ReminderEdited(reminder.clone()));
+    print!("\nrn ({name}) ");
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
-pub async fn reset_reminder_flags(State((db_file, _)): ApiState) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    for reminder in &mut db_file.reminders {
+pub async fn reset_reminder_flags(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let flagged_ids: Vec<usize> = db_file
+        .reminders
+        .iter()
+        .filter(|reminder| reminder.restart_flag() || reminder.delete_flag())
+        .map(Reminder::id)
+        .collect();
+    let recording = Recording::start(&db_file.reminders, &flagged_ids);
+    for reminder in db_file
+        .reminders
+        .iter_mut()
+        .filter(|reminder| current_user.can_access(reminder.owner()))
+    {
         reminder.set_restart_flag(false);
         reminder.set_delete_flag(false);
     }
     print!("\nrrf ");
-    StatusCode::OK
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
+/// Snoozes by the duration in the body, or by the snooze duration of the reminder, or by the configured default.
 pub async fn snooze_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(snooze): Extension<Snooze>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.snooze();
-        *past_event = PastEvent::ReminderSnooze(reminder.clone());
-        print!("\ns ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+    duration: Result<Json<core::time::Duration>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let duration = optional_json_body(duration)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let duration = match duration {
+        Some(duration) => {
+            let Ok(duration) = duration.try_into() else {
+                return Err(ApiError::invalid_input("The duration is too long")
+                    .with_reminder_id(id)
+                    .into());
+            };
+            duration
+        }
+        None => default_snooze_duration(reminder, &snooze),
+    };
+    reminder.snooze(duration);
+    db_file.completions.update(reminder);
+    db_file
+        .events
+        .push(PastEvent::ReminderSnooze(reminder.clone()));
+    print!("\ns ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn delete_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let reminders_clone = db_file.reminders.clone();
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        print!("\nd ({}) ", reminder.name());
-        if reminder.delete_flag() {
-            *past_event = PastEvent::ReminderDeleted(reminder.clone());
-            let Some(index) = reminders_clone
-                .iter()
-                .position(|s_reminder| s_reminder.id() == reminder.id())
-            else {
-                return StatusCode::NOT_FOUND;
-            };
-            db_file.reminders.remove(index);
-        } else {
-            reminder.set_delete_flag(true);
-        }
-        StatusCode::OK
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &linked_ids(&db_file.reminders, id));
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    print!("\nd ({}) ", reminder.name());
+    if reminder.delete_flag() {
+        unlink(&mut db_file.reminders, id);
+        let Some(index) = db_file
+            .reminders
+            .iter()
+            .position(|reminder| reminder.id() == id)
+        else {
+            return Err(ApiError::reminder_not_found(id).into());
+        };
+        let reminder = db_file.reminders.remove(index);
+        db_file.events.push(PastEvent::ReminderDeleted(reminder));
     } else {
-        StatusCode::NOT_FOUND
+        reminder.set_delete_flag(true);
     }
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn retime_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
     retime_object: Result<Json<TimeObject>, JsonRejection>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(retime_object)) = retime_object else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.set_finish_time(retime_object.finish_time);
-        reminder.set_whole_duration(retime_object.duration);
-        reminder.set_reminder_type(retime_object.reminder_type.clone());
-        *past_event = PastEvent::ReminderEdited(reminder.clone());
-        print!("\nrt ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let retime_object = json_body(retime_object)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.retime(retime_object)?;
+    db_file
+        .events
+        .push(PastEvent::ReminderEdited(reminder.clone()));
+    print!("\nrt ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn pause_reminder(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.toggle_pause();
-        *past_event = PastEvent::ReminderPause(reminder.clone());
-        print!("\n' ' ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.toggle_pause();
+    db_file.completions.update(reminder);
+    db_file
+        .events
+        .push(PastEvent::ReminderPause(reminder.clone()));
+    print!("\n' ' ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn toggle_reminder_repeat(
-    State((db_file, past_event)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(mut past_event) = past_event.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        if let Some(toggled) = reminder.toggle_repeat() {
-            if toggled {
-                *past_event = PastEvent::ReminderRepeatToggle(reminder.clone());
-            }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    if let Some(toggled) = reminder.toggle_repeat() {
+        if toggled {
+            db_file
+                .events
+                .push(PastEvent::ReminderRepeatToggle(reminder.clone()));
         }
-        print!("\ne ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
     }
+    print!("\ne ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn set_reminder_recurrence(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    recurrence: Result<Json<Recurrence>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let recurrence = json_body(recurrence)?;
+    check_recurrence(&recurrence).map_err(|error| error.with_reminder_id(id))?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.set_recurrence(recurrence);
+    db_file
+        .events
+        .push(PastEvent::ReminderRepeatToggle(reminder.clone()));
+    print!("\nrr ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn toggle_reminder_send_e_message(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.toggle_send_e_message();
+    db_file
+        .events
+        .push(PastEvent::ReminderSendEMessageToggle(reminder.clone()));
+    print!("\nm ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn toggle_reminder_nag(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.toggle_nag();
+    db_file
+        .events
+        .push(PastEvent::ReminderNagToggle(reminder.clone()));
+    print!("\na ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+/// The snooze duration of `reminder`, or the configured one if it has none.
+pub fn default_snooze_duration(reminder: &Reminder, snooze: &Snooze) -> time::Duration {
+    reminder
+        .snooze_duration()
+        .unwrap_or_else(|| time::Duration::minutes(i64::from(snooze.minutes())))
+}
+
+/// Sets how long `s` snoozes this reminder, `null` goes back to the configured default.
+pub async fn set_reminder_snooze_duration(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    snooze_duration: Result<Json<Option<core::time::Duration>>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let snooze_duration = json_body(snooze_duration)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let snooze_duration = match snooze_duration.map(time::Duration::try_from) {
+        Some(Ok(duration)) => Some(duration),
+        Some(Err(_)) => {
+            return Err(ApiError::invalid_input("The duration is too long")
+                .with_reminder_id(id)
+                .into());
+        }
+        None => None,
+    };
+    reminder.set_snooze_duration(snooze_duration);
+    print!("\nrz ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Sets the lead times of the heads-ups before the reminder is due, an empty list turns them off.
+pub async fn set_reminder_warnings(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    warnings: Result<Json<Vec<core::time::Duration>>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let warnings = json_body(warnings)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let Ok(warnings) = warnings
+        .into_iter()
+        .map(time::Duration::try_from)
+        .collect::<Result<Vec<time::Duration>, _>>()
+    else {
+        return Err(ApiError::invalid_input("The duration is too long")
+            .with_reminder_id(id)
+            .into());
+    };
+    reminder.set_warnings(warnings);
+    print!("\nrw ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn set_reminder_priority(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    priority: Result<Json<Priority>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let priority = json_body(priority)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.set_priority(priority);
+    print!("\nrp ({}, {priority}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Replaces the tags of the reminder, an empty list removes them.
+pub async fn set_reminder_tags(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    tags: Result<Json<Vec<String>>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let tags = json_body(tags)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.set_tags(&tags);
+    print!("\nr# ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn push_reminder_duration(
-    State((db_file, _)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
     amount_to_add: Result<Json<core::time::Duration>, JsonRejection>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(amount_to_add)) = amount_to_add else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        let Ok(duration): Result<time::Duration, _> = amount_to_add.try_into() else {
-            return StatusCode::INTERNAL_SERVER_ERROR;
-        };
-        reminder.set_finish_time(reminder.finish_time().saturating_add(duration));
-        print!("\n+ ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let amount_to_add = json_body(amount_to_add)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let Ok(duration): Result<time::Duration, _> = amount_to_add.try_into() else {
+        return Err(ApiError::invalid_input("The duration is too long")
+            .with_reminder_id(id)
+            .into());
+    };
+    reminder.set_finish_time(reminder.finish_time().saturating_add(duration));
+    print!("\n+ ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn cut_reminder_duration(
-    State((db_file, _)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
     amount_to_subtract: Result<Json<core::time::Duration>, JsonRejection>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(amount_to_subtract)) = amount_to_subtract else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        let Ok(duration): Result<time::Duration, _> = amount_to_subtract.try_into() else {
-            return StatusCode::INTERNAL_SERVER_ERROR;
-        };
-        reminder.set_finish_time(reminder.finish_time().saturating_sub(duration));
-        print!("\n- ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let amount_to_subtract = json_body(amount_to_subtract)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let Ok(duration): Result<time::Duration, _> = amount_to_subtract.try_into() else {
+        return Err(ApiError::invalid_input("The duration is too long")
+            .with_reminder_id(id)
+            .into());
+    };
+    reminder.set_finish_time(reminder.finish_time().saturating_sub(duration));
+    print!("\n- ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
 pub async fn alter_reminder_description(
-    State((db_file, _)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
     new_description: Result<Json<String>, JsonRejection>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    let Ok(Json(new_description)) = new_description else {
-        return StatusCode::UNPROCESSABLE_ENTITY;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.set_description(new_description.clone());
-        print!("\n\\n ({}) ", reminder.name());
-        StatusCode::OK
-    } else {
-        StatusCode::NOT_FOUND
-    }
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let new_description = json_body(new_description)?;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.set_description(new_description.clone());
+    print!("\n\\n ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
+/// Acknowledges a reminder, which stops the alerts on every client.
 pub async fn confirm_reminder_finish_event(
-    State((db_file, _)): ApiState,
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
     Path(id): Path<usize>,
-) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
-    if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
-        reminder.confirm_finish_event();
-        print!("\nc ({}) ", reminder.name());
-        StatusCode::OK
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let due = reminder.needs_confirmation();
+    reminder.confirm_finish_event();
+    if due {
+        db_file.completions.confirmed(reminder);
+    }
+    print!("\nc ({}) ", reminder.name());
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn pop_reminder_history(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let user_name = current_user.name();
+    if let Some(label) = db_file
+        .operations
+        .undo(user_name.as_deref(), &mut db_file.reminders)
+    {
+        print!("\nu ({label}) ");
+        return Ok(StatusCode::OK);
+    };
+    Err(ApiError::new(ApiErrorCode::NothingToUndo, "There is nothing to undo").into())
+}
+
+pub async fn pop_reminder_redo_history(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let user_name = current_user.name();
+    if let Some(label) = db_file
+        .operations
+        .redo(user_name.as_deref(), &mut db_file.reminders)
+    {
+        print!("\nU ({label}) ");
+        return Ok(StatusCode::OK);
+    };
+    Err(ApiError::new(ApiErrorCode::NothingToRedo, "There is nothing to redo").into())
+}
+
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    /// Only completions due in the last `days` days, all of them if not set.
+    days: Option<u32>,
+}
+
+/// Summarizes the completion history of the reminders the calling user can see.
+pub async fn get_stats(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<StatsQuery>,
+) -> ApiResult<Json<CompletionStats>> {
+    let db_file = lock(&db_file)?;
+    let since = query.days.map(|days| {
+        OffsetDateTime::now_utc()
+            .to_offset(my_local_offset())
+            .saturating_sub(time::Duration::days(i64::from(days)))
+    });
+    Ok(Json(CompletionStats::new(
+        db_file
+            .completions
+            .completions()
+            .iter()
+            .filter(|completion| {
+                current_user.can_access(completion.owner())
+                    && since.is_none_or(|since| completion.due_time() >= since)
+            }),
+    )))
+}
+
+pub async fn get_reminder_history(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Json<HistoryPreview>> {
+    let db_file = lock(&db_file)?;
+    Ok(Json(
+        db_file.operations.preview(current_user.name().as_deref()),
+    ))
+}
+
+/// Parses a time input the same way the terminal client does, so every frontend understands the same inputs.
+pub async fn parse_time(body: Result<Json<String>, JsonRejection>) -> ApiResult<Json<TimeObject>> {
+    let input = json_body(body)?;
+    let Ok(time_object) = parse_time_input(
+        input.as_str(),
+        OffsetDateTime::now_utc().to_offset(my_local_offset()),
+    ) else {
+        return Err(ApiError::invalid_input(format!(
+            "Could not read '{input}' as one of {TIME_INPUT_EXAMPLES}"
+        ))
+        .into());
+    };
+    Ok(Json(time_object))
+}
+
+pub async fn all_templates(
+    State((library, _)): LibraryState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Json<Vec<ReminderTemplate>>> {
+    let library = lock(&library)?;
+    Ok(Json(
+        library
+            .templates
+            .iter()
+            .filter(|template| current_user.can_access(template.owner()))
+            .cloned()
+            .collect(),
+    ))
+}
+
+pub async fn save_reminder_as_template(
+    State((library, db_file)): LibraryState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<usize>,
+) -> ApiResult {
+    let mut library = lock(&library)?;
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let mut template = ReminderTemplate::from_reminder(reminder);
+    template.set_owner(current_user.name());
+    if let Some(existing_template) = library.templates.iter_mut().find(|existing_template| {
+        existing_template.name() == template.name() && existing_template.owner() == template.owner()
+    }) {
+        *existing_template = template.clone();
     } else {
-        StatusCode::NOT_FOUND
+        library.templates.push(template.clone());
     }
+    print!("\nL ({}) ", template.name());
+    db_file.events.push(PastEvent::TemplateSaved(template));
+    if library.write().is_err() {
+        return Err(ApiError::internal("Could not write the reminder library").into());
+    }
+    Ok(StatusCode::OK)
 }
 
-pub async fn pop_reminder_history(State((db_file, _)): ApiState) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
+pub async fn delete_template(
+    State((library, _)): LibraryState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(index): Path<usize>,
+) -> ApiResult {
+    let mut library = lock(&library)?;
+    let Some(index) = visible_template_index(&library.templates, index, &current_user) else {
+        return Err(template_not_found(index));
+    };
+    let template = library.templates.remove(index);
+    print!("\ndL ({}) ", template.name());
+    if library.write().is_err() {
+        return Err(ApiError::internal("Could not write the reminder library").into());
+    }
+    Ok(StatusCode::OK)
+}
 
-    let current_reminders = db_file.reminders.clone();
-    db_file.redoable_history.push(current_reminders);
-    if let Some(history_reminders) = db_file.history.pop() {
-        db_file.reset_history_on_change = true;
-        db_file.reminders = history_reminders;
-        return StatusCode::OK;
-    };
-    StatusCode::INTERNAL_SERVER_ERROR
+pub async fn instantiate_template(
+    State((library, db_file)): LibraryState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(index): Path<usize>,
+) -> ApiResult {
+    let library = lock(&library)?;
+    let mut db_file = lock(&db_file)?;
+    let Some(template) = visible_template_index(&library.templates, index, &current_user)
+        .and_then(|index| library.templates.get(index))
+    else {
+        return Err(template_not_found(index));
+    };
+    let new_id = next_reminder_id(&mut db_file);
+    let recording = Recording::start(&db_file.reminders, &[new_id]);
+    let mut new_reminder = Reminder::from_api_reminder(new_id, template.to_api_reminder())?;
+    new_reminder.set_owner(current_user.name());
+    db_file.reminders.push(new_reminder.clone());
+    db_file
+        .events
+        .push(PastEvent::ReminderCreated(new_reminder.clone()));
+    print!("\nl ({}) ", new_reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
 }
 
-pub async fn pop_reminder_redo_history(State((db_file, _)): ApiState) -> StatusCode {
-    let Ok(mut db_file) = db_file.lock() else {
-        return StatusCode::INTERNAL_SERVER_ERROR;
-    };
+/// Maps the `index` a user sees in `/library` to the index in the whole library.
+fn visible_template_index(
+    templates: &[ReminderTemplate],
+    index: usize,
+    current_user: &CurrentUser,
+) -> Option<usize> {
+    templates
+        .iter()
+        .enumerate()
+        .filter(|(_, template)| current_user.can_access(template.owner()))
+        .nth(index)
+        .map(|(index, _)| index)
+}
 
-    let current_reminders = db_file.reminders.clone();
-    db_file.history.push(current_reminders);
-    if let Some(redo_history_reminders) = db_file.redoable_history.pop() {
-        db_file.reset_history_on_change = true;
-        db_file.reminders = redo_history_reminders;
-        return StatusCode::OK;
-    };
-    StatusCode::INTERNAL_SERVER_ERROR
+fn template_not_found(index: usize) -> ApiErrorResponse {
+    ApiError::new(
+        ApiErrorCode::TemplateNotFound,
+        format!("There is no template number {}", index.saturating_add(1)),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use reminder::{
+        api_error::ApiError,
+        priority::Priority,
+        reminder::{ApiReminder, Reminder, ReminderType},
+    };
+    use time::{Duration, OffsetDateTime};
+
+    use super::next_reminder_id;
+    use crate::DBFile;
+
+    fn reminder(id: usize) -> Result<Reminder, ApiError> {
+        Reminder::from_api_reminder(
+            id,
+            ApiReminder {
+                name: format!("reminder {id}"),
+                description: String::new(),
+                finish_time: OffsetDateTime::now_utc().saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Duration,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )
+    }
+
+    #[test]
+    fn ids_of_deleted_reminders_are_never_handed_out_again() -> Result<(), ApiError> {
+        let mut db_file = DBFile {
+            reminders: vec![reminder(1)?, reminder(2)?],
+            ..DBFile::default()
+        };
+        let id = next_reminder_id(&mut db_file);
+        assert_eq!(id, 3);
+        db_file.reminders.push(reminder(id)?);
+        db_file.completions.fired(&reminder(id)?);
+        db_file.reminders.retain(|reminder| reminder.id() != id);
+        assert_eq!(next_reminder_id(&mut db_file), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn older_databases_continue_after_the_completion_history() -> Result<(), ApiError> {
+        let mut db_file = DBFile {
+            reminders: vec![reminder(1)?],
+            ..DBFile::default()
+        };
+        // Reminder 7 was deleted before `last_reminder_id` was stored
+        db_file.completions.fired(&reminder(7)?);
+        assert_eq!(next_reminder_id(&mut db_file), 8);
+        assert_eq!(next_reminder_id(&mut db_file), 9);
+        Ok(())
+    }
 }
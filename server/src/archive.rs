@@ -0,0 +1,331 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use config::Retention;
+use json_store_rs::JsonStore;
+use reminder::{
+    api_error::{ApiError, ApiErrorCode},
+    archive::ArchivedReminder,
+    chain::unlink,
+    past_event::PastEvent,
+    reminder::{my_local_offset, Reminder},
+    root_path, REMINDER_ARCHIVE_FILE,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    api::next_reminder_id,
+    auth::CurrentUser,
+    error::{lock, ApiErrorResponse, ApiResult},
+    DBFile,
+};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Archive {
+    reminders: Vec<ArchivedReminder>,
+}
+
+impl Archive {
+    pub fn reminders_mut(&mut self) -> &mut [ArchivedReminder] {
+        &mut self.reminders
+    }
+}
+
+impl JsonStore for Archive {
+    fn db_file_path() -> PathBuf {
+        let mut root_path = root_path().unwrap_or_default();
+        root_path.push(REMINDER_ARCHIVE_FILE);
+        root_path
+    }
+}
+
+type ArchiveState = State<(Arc<Mutex<Archive>>, Arc<Mutex<DBFile>>)>;
+
+/// Deletes or archives every reminder, that the retention policy is done with.
+/// Returns if anything was removed from the reminders.
+pub fn enforce_retention(
+    db_file: &mut DBFile,
+    archive: &Mutex<Archive>,
+    retention: &Retention,
+) -> bool {
+    if !retention.after_confirmation() && retention.after_hours().is_none() {
+        return false;
+    }
+    let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+    let expired_ids: Vec<usize> = db_file
+        .reminders
+        .iter()
+        .filter(|reminder| is_expired(reminder, retention, now))
+        .map(Reminder::id)
+        .collect();
+    if expired_ids.is_empty() {
+        return false;
+    }
+    // Locked before anything changes, so a poisoned archive leaves the reminders untouched
+    let mut archive = if retention.archive() {
+        let Ok(archive) = archive.lock() else {
+            return false;
+        };
+        Some(archive)
+    } else {
+        None
+    };
+    for id in &expired_ids {
+        unlink(&mut db_file.reminders, *id);
+    }
+    let (expired, kept): (Vec<Reminder>, Vec<Reminder>) = std::mem::take(&mut db_file.reminders)
+        .into_iter()
+        .partition(|reminder| expired_ids.contains(&reminder.id()));
+    db_file.reminders = kept;
+    if let Some(archive) = &mut archive {
+        for reminder in expired {
+            print!("\narchived ({}) ", reminder.name().trim_end());
+            db_file
+                .events
+                .push(PastEvent::ReminderArchived(reminder.clone()));
+            archive.reminders.push(ArchivedReminder::new(reminder));
+        }
+        let _trash_bin = archive.write();
+    } else {
+        for reminder in expired {
+            print!("\ncleaned up ({}) ", reminder.name().trim_end());
+            db_file.events.push(PastEvent::ReminderDeleted(reminder));
+        }
+    }
+    true
+}
+
+/// `after_confirmation` waits for the confirmation, `after_hours` only for the hours after the finish time,
+/// so reminders nobody acknowledged expire as well.
+fn is_expired(reminder: &Reminder, retention: &Retention, now: OffsetDateTime) -> bool {
+    if reminder.is_note()
+        || reminder.repeating()
+        || reminder.paused()
+        || reminder.remaining_duration().is_some()
+    {
+        return false;
+    }
+    (retention.after_confirmation() && reminder.already_confirmed())
+        || retention.after_hours().is_some_and(|hours| {
+            reminder
+                .finish_time()
+                .saturating_add(Duration::hours(i64::from(hours)))
+                < now
+        })
+}
+
+pub async fn all_archived(
+    State((archive, _)): ArchiveState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Json<Vec<ArchivedReminder>>> {
+    let archive = lock(&archive)?;
+    Ok(Json(
+        archive
+            .reminders
+            .iter()
+            .filter(|archived| current_user.can_access(archived.reminder().owner()))
+            .cloned()
+            .collect(),
+    ))
+}
+
+/// Moves an archived reminder back into the reminder list and restarts it.
+/// It keeps its id, unless a new reminder took it in the meantime.
+pub async fn restore_archived(
+    State((archive, db_file)): ArchiveState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(index): Path<usize>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let mut archive = lock(&archive)?;
+    let Some(index) = visible_archive_index(&archive.reminders, index, &current_user) else {
+        return Err(archived_not_found(index));
+    };
+    let mut reminder = archive.reminders.remove(index).into_reminder();
+    if db_file
+        .reminders
+        .iter()
+        .any(|existing| existing.id() == reminder.id())
+    {
+        reminder.set_id(next_reminder_id(db_file));
+    }
+    reminder.restart();
+    print!("\nrestored ({}) ", reminder.name().trim_end());
+    db_file
+        .events
+        .push(PastEvent::ReminderRestored(reminder.clone()));
+    db_file.reminders.push(reminder);
+    if archive.write().is_err() {
+        return Err(ApiError::internal("Could not write the archive").into());
+    }
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_archived(
+    State((archive, _)): ArchiveState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(index): Path<usize>,
+) -> ApiResult {
+    let mut archive = lock(&archive)?;
+    let Some(index) = visible_archive_index(&archive.reminders, index, &current_user) else {
+        return Err(archived_not_found(index));
+    };
+    let archived = archive.reminders.remove(index);
+    print!("\nad ({}) ", archived.reminder().name().trim_end());
+    if archive.write().is_err() {
+        return Err(ApiError::internal("Could not write the archive").into());
+    }
+    Ok(StatusCode::OK)
+}
+
+/// Maps the `index` a user sees in `/archive` to the index in the whole archive.
+fn visible_archive_index(
+    archived: &[ArchivedReminder],
+    index: usize,
+    current_user: &CurrentUser,
+) -> Option<usize> {
+    archived
+        .iter()
+        .enumerate()
+        .filter(|(_, archived)| current_user.can_access(archived.reminder().owner()))
+        .nth(index)
+        .map(|(index, _)| index)
+}
+
+fn archived_not_found(index: usize) -> ApiErrorResponse {
+    ApiError::new(
+        ApiErrorCode::ReminderNotFound,
+        format!(
+            "There is no archived reminder number {}",
+            index.saturating_add(1)
+        ),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use config::Retention;
+    use reminder::{
+        chain::chain_ids,
+        priority::Priority,
+        reminder::{my_local_offset, ApiReminder, Reminder, ReminderType},
+    };
+    use serde_json::json;
+    use time::{Duration, OffsetDateTime};
+
+    use super::{enforce_retention, is_expired, Archive};
+    use crate::DBFile;
+
+    /// A reminder, that finished `hours_ago`.
+    fn finished_reminder(id: usize, hours_ago: i64) -> Option<Reminder> {
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        let mut reminder = Reminder::from_api_reminder(
+            id,
+            ApiReminder {
+                name: format!("reminder {id}"),
+                description: String::new(),
+                finish_time: now.saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Duration,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )
+        .ok()?;
+        reminder.set_finish_time(now.saturating_sub(Duration::hours(hours_ago)));
+        Some(reminder)
+    }
+
+    fn retention(policy: serde_json::Value) -> Option<Retention> {
+        serde_json::from_value(policy).ok()
+    }
+
+    #[test]
+    fn after_confirmation_waits_for_the_confirmation() -> Result<(), &'static str> {
+        let retention = retention(json!({ "after_confirmation": true })).ok_or("retention")?;
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        let mut reminder = finished_reminder(1, 100).ok_or("reminder")?;
+        assert!(!is_expired(&reminder, &retention, now));
+        reminder.confirm_finish_event();
+        assert!(is_expired(&reminder, &retention, now));
+        Ok(())
+    }
+
+    #[test]
+    fn after_hours_expires_unconfirmed_reminders() -> Result<(), &'static str> {
+        let retention = retention(json!({ "after_hours": 24 })).ok_or("retention")?;
+        let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+        assert!(!is_expired(
+            &finished_reminder(1, 2).ok_or("reminder")?,
+            &retention,
+            now
+        ));
+        assert!(is_expired(
+            &finished_reminder(1, 25).ok_or("reminder")?,
+            &retention,
+            now
+        ));
+        let mut paused = finished_reminder(1, 25).ok_or("reminder")?;
+        paused.toggle_pause();
+        assert!(!is_expired(&paused, &retention, now));
+        Ok(())
+    }
+
+    #[test]
+    fn expired_reminders_leave_their_chain() -> Result<(), &'static str> {
+        let retention = retention(json!({ "after_hours": 24 })).ok_or("retention")?;
+        let mut first = finished_reminder(1, 2).ok_or("reminder")?;
+        first.set_next_in_chain(Some(2));
+        let mut db_file = DBFile {
+            reminders: vec![first, finished_reminder(2, 25).ok_or("reminder")?],
+            ..DBFile::default()
+        };
+        assert!(enforce_retention(
+            &mut db_file,
+            &Mutex::new(Archive::default()),
+            &retention
+        ));
+        assert_eq!(db_file.reminders.len(), 1);
+        assert_eq!(chain_ids(&db_file.reminders, 1), vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn a_poisoned_archive_leaves_the_chain_intact() -> Result<(), &'static str> {
+        let retention =
+            retention(json!({ "after_hours": 24, "archive": true })).ok_or("retention")?;
+        let mut first = finished_reminder(1, 2).ok_or("reminder")?;
+        first.set_next_in_chain(Some(2));
+        let mut db_file = DBFile {
+            reminders: vec![first, finished_reminder(2, 25).ok_or("reminder")?],
+            ..DBFile::default()
+        };
+        let archive = Mutex::new(Archive::default());
+        let _poisoned = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let _guard = archive.lock();
+                    panic!("poisons the archive");
+                })
+                .join()
+        });
+        assert!(archive.is_poisoned());
+        assert!(!enforce_retention(&mut db_file, &archive, &retention));
+        assert_eq!(db_file.reminders.len(), 2);
+        assert_eq!(chain_ids(&db_file.reminders, 1), vec![1, 2]);
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use config::User;
+use reminder::{
+    api_error::{ApiError, ApiErrorCode},
+    archive::ArchivedReminder,
+};
+use subtle::ConstantTimeEq;
+
+use crate::{
+    archive::Archive,
+    error::{lock, ApiResult},
+    DBFile, ReminderLibrary,
+};
+
+/// The user behind a request, resolved from its `Authorization: Bearer <token>` header.
+/// Holds `None` if no `[[auth.users]]` are configured, which grants access to everything.
+#[derive(Clone)]
+pub struct CurrentUser(Option<String>);
+
+impl CurrentUser {
+    #[must_use]
+    pub fn name(&self) -> Option<String> {
+        self.0.clone()
+    }
+    /// Once users are configured, only the owner has access, see `claim_ownerless`.
+    #[must_use]
+    pub fn can_access(&self, owner: Option<&str>) -> bool {
+        match self.0.as_deref() {
+            Some(user) => owner == Some(user),
+            None => true,
+        }
+    }
+}
+
+/// Compares every token in constant time, so response times do not leak how much of a token matched.
+fn user_by_token<'a>(users: &'a [User], token: &str) -> Option<&'a User> {
+    users.iter().fold(None, |found, user| {
+        if bool::from(user.token().as_bytes().ct_eq(token.as_bytes())) {
+            Some(user)
+        } else {
+            found
+        }
+    })
+}
+
+/// Hands every reminder, template and archived reminder without an owner to `default_user`.
+/// They were created before authentication was set up, and nobody else can access them now.
+/// Returns how many were claimed.
+pub fn claim_ownerless(
+    default_user: &str,
+    db_file: &mut DBFile,
+    library: &mut ReminderLibrary,
+    archive: &mut Archive,
+) -> usize {
+    let mut claimed: usize = 0;
+    let reminders = db_file.reminders.iter_mut().chain(
+        archive
+            .reminders_mut()
+            .iter_mut()
+            .map(ArchivedReminder::reminder_mut),
+    );
+    for reminder in reminders.filter(|reminder| reminder.owner().is_none()) {
+        reminder.set_owner(Some(default_user.to_string()));
+        claimed = claimed.saturating_add(1);
+    }
+    for template in library
+        .templates
+        .iter_mut()
+        .filter(|template| template.owner().is_none())
+    {
+        template.set_owner(Some(default_user.to_string()));
+        claimed = claimed.saturating_add(1);
+    }
+    claimed
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub async fn authenticate(
+    State(users): State<Arc<Vec<User>>>,
+    mut req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    let current_user = if users.is_empty() {
+        CurrentUser(None)
+    } else {
+        let Some(user) = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| user_by_token(&users, token))
+        else {
+            return Err(ApiError::new(
+                ApiErrorCode::Unauthorized,
+                "Missing or unknown token, set `token` in the `[network]` section",
+            )
+            .into());
+        };
+        CurrentUser(Some(user.name().clone()))
+    };
+    req.extensions_mut().insert(current_user);
+    Ok(next.run(req).await)
+}
+
+/// Rejects requests to `/reminders/:id/..` routes of reminders, that belong to another user.
+/// They get the same `NOT_FOUND` as an unknown id, so nobody can probe for foreign reminders.
+#[allow(clippy::missing_errors_doc)]
+pub async fn authorize_reminder_access(
+    State(db_file): State<Arc<Mutex<DBFile>>>,
+    Extension(current_user): Extension<CurrentUser>,
+    path: Option<Path<HashMap<String, String>>>,
+    req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    if let Some(id) = path.and_then(|Path(params)| params.get("id")?.parse::<usize>().ok()) {
+        if !lock(&db_file)?
+            .reminders
+            .iter()
+            .find(|reminder| reminder.id() == id)
+            .is_some_and(|reminder| current_user.can_access(reminder.owner()))
+        {
+            return Err(ApiError::reminder_not_found(id).into());
+        }
+    }
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        routing::put,
+        Router,
+    };
+    use config::User;
+    use reminder::{
+        api_error::ApiError,
+        library::ReminderTemplate,
+        priority::Priority,
+        reminder::{ApiReminder, Reminder, ReminderType},
+    };
+    use serde_json::json;
+    use time::{Duration, OffsetDateTime};
+    use tower::ServiceExt;
+
+    use super::{
+        authenticate, authorize_reminder_access, claim_ownerless, user_by_token, CurrentUser,
+    };
+    use crate::{archive::Archive, DBFile, ReminderLibrary};
+
+    fn reminder(id: usize, owner: Option<&str>) -> Result<Reminder, ApiError> {
+        let mut reminder = Reminder::from_api_reminder(
+            id,
+            ApiReminder {
+                name: format!("reminder {id}"),
+                description: String::new(),
+                finish_time: OffsetDateTime::now_utc().saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Duration,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )?;
+        reminder.set_owner(owner.map(String::from));
+        Ok(reminder)
+    }
+
+    fn users() -> Vec<User> {
+        serde_json::from_value(json!([
+            { "name": "jonas", "token": "jonas-token" },
+            { "name": "mira", "token": "mira-token" },
+        ]))
+        .unwrap_or_default()
+    }
+
+    /// The answer to `PUT /reminders/:id/pause` behind both auth middlewares.
+    async fn status(db_file: DBFile, id: usize, token: Option<&str>) -> StatusCode {
+        let db_file = Arc::new(Mutex::new(db_file));
+        let app = Router::new()
+            .route("/reminders/:id/pause", put(|| async { StatusCode::OK }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                Arc::clone(&db_file),
+                authorize_reminder_access,
+            ))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(users()),
+                authenticate,
+            ));
+        let mut request = Request::put(format!("/reminders/{id}/pause"));
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let Ok(request) = request.body(Body::empty()) else {
+            return StatusCode::BAD_REQUEST;
+        };
+        app.oneshot(request)
+            .await
+            .map_or(StatusCode::INTERNAL_SERVER_ERROR, |response| {
+                response.status()
+            })
+    }
+
+    #[test]
+    fn tokens_resolve_to_their_user() {
+        let users = users();
+        assert_eq!(
+            user_by_token(&users, "mira-token").map(User::name),
+            Some(&"mira".to_string())
+        );
+        assert!(user_by_token(&users, "mira-toke").is_none());
+        assert!(user_by_token(&users, "").is_none());
+    }
+
+    #[test]
+    fn only_the_owner_can_access_once_users_are_configured() {
+        let jonas = CurrentUser(Some("jonas".to_string()));
+        assert!(jonas.can_access(Some("jonas")));
+        assert!(!jonas.can_access(Some("mira")));
+        assert!(!jonas.can_access(None));
+        let anyone = CurrentUser(None);
+        assert!(anyone.can_access(Some("mira")));
+        assert!(anyone.can_access(None));
+    }
+
+    #[test]
+    fn ownerless_reminders_and_templates_go_to_the_default_user() -> Result<(), ApiError> {
+        let mut db_file = DBFile {
+            reminders: vec![reminder(1, None)?, reminder(2, Some("mira"))?],
+            ..DBFile::default()
+        };
+        let mut library = ReminderLibrary {
+            templates: vec![ReminderTemplate::from_reminder(&reminder(3, None)?)],
+        };
+        let claimed = claim_ownerless("jonas", &mut db_file, &mut library, &mut Archive::default());
+        assert_eq!(claimed, 2);
+        let owners: Vec<Option<&str>> = db_file.reminders.iter().map(Reminder::owner).collect();
+        assert_eq!(owners, vec![Some("jonas"), Some("mira")]);
+        assert_eq!(
+            library.templates.first().and_then(ReminderTemplate::owner),
+            Some("jonas")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn requests_without_a_known_token_are_unauthorized() -> Result<(), ApiError> {
+        let db_file = || -> Result<DBFile, ApiError> {
+            Ok(DBFile {
+                reminders: vec![reminder(1, Some("jonas"))?],
+                ..DBFile::default()
+            })
+        };
+        assert_eq!(status(db_file()?, 1, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(db_file()?, 1, Some("wrong-token")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(db_file()?, 1, Some("jonas-token")).await,
+            StatusCode::OK
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn foreign_and_ownerless_reminders_are_not_found() -> Result<(), ApiError> {
+        let db_file = || -> Result<DBFile, ApiError> {
+            Ok(DBFile {
+                reminders: vec![reminder(1, Some("jonas"))?, reminder(2, None)?],
+                ..DBFile::default()
+            })
+        };
+        assert_eq!(
+            status(db_file()?, 1, Some("mira-token")).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(db_file()?, 2, Some("mira-token")).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(db_file()?, 3, Some("mira-token")).await,
+            StatusCode::NOT_FOUND
+        );
+        Ok(())
+    }
+}
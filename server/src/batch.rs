@@ -0,0 +1,233 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use config::Snooze;
+use reminder::{
+    api_error::ApiError,
+    chain::{linked_ids, unlink},
+    history::Recording,
+    past_event::PastEvent,
+    reminder::{BatchRequest, Reminder},
+};
+
+use crate::{
+    api::default_snooze_duration,
+    auth::CurrentUser,
+    error::{json_body, lock, ApiResult},
+    history::HistoryEntry,
+    DBFile,
+};
+
+type ApiState = State<Arc<Mutex<DBFile>>>;
+
+/// Looks up every requested reminder before anything is changed, so a batch applies to all of them or to none.
+fn batch_positions(
+    reminders: &[Reminder],
+    ids: &[usize],
+    current_user: &CurrentUser,
+) -> ApiResult<Vec<usize>> {
+    if ids.is_empty() {
+        return Err(ApiError::invalid_input("A batch needs at least one reminder").into());
+    }
+    let mut positions = vec![];
+    for id in ids {
+        let Some(position) = reminders
+            .iter()
+            .position(|reminder| reminder.id() == *id && current_user.can_access(reminder.owner()))
+        else {
+            return Err(ApiError::reminder_not_found(*id).into());
+        };
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+    Ok(positions)
+}
+
+fn batch_duration(duration: Option<core::time::Duration>) -> ApiResult<Option<time::Duration>> {
+    let Some(duration) = duration else {
+        return Ok(None);
+    };
+    let Ok(duration) = duration.try_into() else {
+        return Err(ApiError::invalid_input("The duration is too long").into());
+    };
+    Ok(Some(duration))
+}
+
+fn required_batch_duration(duration: Option<core::time::Duration>) -> ApiResult<time::Duration> {
+    batch_duration(duration)?
+        .ok_or_else(|| ApiError::invalid_input("This batch needs a duration").into())
+}
+
+fn print_batch(action: &str, reminders: &[Reminder], positions: &[usize]) {
+    let names: Vec<&str> = positions
+        .iter()
+        .filter_map(|position| reminders.get(*position))
+        .map(|reminder| reminder.name().trim_end())
+        .collect();
+    print!("\nv{action} ({}) ", names.join(", "));
+}
+
+/// Deletes right away, selecting the reminders already was the confirmation.
+pub async fn batch_delete(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(
+        &db_file.reminders,
+        &batch
+            .ids
+            .iter()
+            .flat_map(|id| linked_ids(&db_file.reminders, *id))
+            .collect::<Vec<usize>>(),
+    );
+    let mut positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    print_batch("d", &db_file.reminders, &positions);
+    for id in &batch.ids {
+        unlink(&mut db_file.reminders, *id);
+    }
+    positions.sort_unstable_by(|a, b| b.cmp(a));
+    for position in positions {
+        let reminder = db_file.reminders.remove(position);
+        db_file.events.push(PastEvent::ReminderDeleted(reminder));
+    }
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Pauses all reminders, or resumes all of them if every one is paused already.
+pub async fn batch_pause(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(&db_file.reminders, &batch.ids);
+    let positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    let pause = positions
+        .iter()
+        .filter_map(|position| db_file.reminders.get(*position))
+        .any(|reminder| !reminder.paused());
+    for position in &positions {
+        let Some(reminder) = db_file.reminders.get_mut(*position) else {
+            continue;
+        };
+        if reminder.paused() != pause {
+            reminder.toggle_pause();
+            db_file.completions.update(reminder);
+            db_file
+                .events
+                .push(PastEvent::ReminderPause(reminder.clone()));
+        }
+    }
+    print_batch("' '", &db_file.reminders, &positions);
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn batch_snooze(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(snooze): Extension<Snooze>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(&db_file.reminders, &batch.ids);
+    let duration = batch_duration(batch.duration)?;
+    let positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    for position in &positions {
+        let Some(reminder) = db_file.reminders.get_mut(*position) else {
+            continue;
+        };
+        reminder.snooze(duration.unwrap_or_else(|| default_snooze_duration(reminder, &snooze)));
+        db_file.completions.update(reminder);
+        db_file
+            .events
+            .push(PastEvent::ReminderSnooze(reminder.clone()));
+    }
+    print_batch("s", &db_file.reminders, &positions);
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn batch_push_duration(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(&db_file.reminders, &batch.ids);
+    let duration = required_batch_duration(batch.duration)?;
+    let positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    for position in &positions {
+        if let Some(reminder) = db_file.reminders.get_mut(*position) {
+            reminder.set_finish_time(reminder.finish_time().saturating_add(duration));
+        }
+    }
+    print_batch("+", &db_file.reminders, &positions);
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn batch_cut_duration(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(&db_file.reminders, &batch.ids);
+    let duration = required_batch_duration(batch.duration)?;
+    let positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    for position in &positions {
+        if let Some(reminder) = db_file.reminders.get_mut(*position) {
+            reminder.set_finish_time(reminder.finish_time().saturating_sub(duration));
+        }
+    }
+    print_batch("-", &db_file.reminders, &positions);
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn batch_confirm(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    batch: Result<Json<BatchRequest>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let batch = json_body(batch)?;
+    let recording = Recording::start(&db_file.reminders, &batch.ids);
+    let positions = batch_positions(&db_file.reminders, &batch.ids, &current_user)?;
+    for position in &positions {
+        if let Some(reminder) = db_file.reminders.get_mut(*position) {
+            let due = reminder.needs_confirmation();
+            reminder.confirm_finish_event();
+            if due {
+                db_file.completions.confirmed(reminder);
+            }
+        }
+    }
+    print_batch("c", &db_file.reminders, &positions);
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
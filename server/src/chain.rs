@@ -0,0 +1,145 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use reminder::{
+    api_error::ApiError,
+    chain::{chain_ids, check_link},
+    get_reminder_by_id,
+    history::Recording,
+    past_event::PastEvent,
+    reminder::{ApiReminder, Reminder},
+};
+
+use crate::{
+    api::next_reminder_id,
+    auth::CurrentUser,
+    error::{json_body, lock, reminder_by_id, ApiResult},
+    history::HistoryEntry,
+    DBFile,
+};
+
+type ApiState = State<Arc<Mutex<DBFile>>>;
+
+/// Creates the reminders in the body as one chain and starts its first reminder,
+/// every following one restarts once the one before it ended.
+pub async fn add_chain(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    api_reminders: Result<Json<Vec<ApiReminder>>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let api_reminders = json_body(api_reminders)?;
+    if api_reminders.is_empty() {
+        return Err(ApiError::invalid_input("A chain needs at least one reminder").into());
+    }
+    let chain_ids: Vec<usize> = api_reminders
+        .iter()
+        .map(|_| next_reminder_id(db_file))
+        .collect();
+    let recording = Recording::start(&db_file.reminders, &chain_ids);
+    let mut chain: Vec<Reminder> = vec![];
+    for (id, api_reminder) in chain_ids.iter().copied().zip(api_reminders) {
+        let mut new_reminder = Reminder::from_api_reminder(id, api_reminder)?;
+        new_reminder.set_owner(current_user.name());
+        if let Some(previous) = chain.last_mut() {
+            previous.set_next_in_chain(Some(id));
+            new_reminder.wait_in_chain();
+        }
+        chain.push(new_reminder);
+    }
+    print!(
+        "\nnc ({}) ",
+        chain
+            .iter()
+            .map(Reminder::name)
+            .collect::<Vec<&str>>()
+            .join(" -> ")
+    );
+    for new_reminder in chain {
+        db_file
+            .events
+            .push(PastEvent::ReminderCreated(new_reminder.clone()));
+        db_file.reminders.push(new_reminder);
+    }
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Sets the reminder, that restarts once this one ended, `null` ends the chain here.
+pub async fn link_reminder(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<usize>,
+    next_id: Result<Json<Option<usize>>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let next_id = json_body(next_id)?;
+    let linked_ids: Vec<usize> = [
+        Some(id),
+        get_reminder_by_id(&mut db_file.reminders, id)
+            .and_then(|reminder| reminder.next_in_chain()),
+        next_id,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let recording = Recording::start(&db_file.reminders, &linked_ids);
+    let reminders = &mut db_file.reminders;
+    let reminder = reminder_by_id(reminders, id)?;
+    let running = reminder.remaining_duration().is_some();
+    let previous_next_id = reminder.next_in_chain();
+    if let Some(next_id) = next_id {
+        if !get_reminder_by_id(reminders, next_id)
+            .is_some_and(|next| current_user.can_access(next.owner()))
+        {
+            return Err(ApiError::reminder_not_found(next_id).into());
+        }
+        check_link(reminders, id, next_id)?;
+    }
+    if let Some(previous_next) = previous_next_id
+        .and_then(|previous_next_id| get_reminder_by_id(reminders, previous_next_id))
+    {
+        if previous_next.waiting() {
+            previous_next.restart();
+        }
+    }
+    let reminder = reminder_by_id(reminders, id)?;
+    reminder.set_next_in_chain(next_id);
+    print!("\nrl ({}) ", reminder.name());
+    if let Some(next) = next_id.and_then(|next_id| get_reminder_by_id(reminders, next_id)) {
+        if running {
+            next.wait_in_chain();
+        }
+    }
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+/// Restarts the chain from this reminder on, every following reminder waits for its turn again.
+pub async fn start_chain(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &chain_ids(&db_file.reminders, id));
+    let reminders = &mut db_file.reminders;
+    let reminder = reminder_by_id(reminders, id)?;
+    reminder.confirm_finish_event();
+    reminder.restart();
+    print!("\nrc ({}) ", reminder.name());
+    for next_id in chain_ids(reminders, id).into_iter().skip(1) {
+        if let Some(next) = get_reminder_by_id(reminders, next_id) {
+            next.wait_in_chain();
+        }
+    }
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use reminder::{
+    api_error::{ApiError, ApiErrorCode},
+    history::Recording,
+};
+
+use crate::{
+    error::{json_body, lock, reminder_by_id, ApiErrorResponse, ApiResult},
+    history::HistoryEntry,
+    DBFile,
+};
+
+type ApiState = State<Arc<Mutex<DBFile>>>;
+
+/// Appends an unchecked item to the checklist of the reminder.
+pub async fn add_checklist_item(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path(id): Path<usize>,
+    text: Result<Json<String>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let text = json_body(text)?;
+    if text.trim().is_empty() {
+        return Err(ApiError::invalid_input("A checklist item needs a text")
+            .with_reminder_id(id)
+            .into());
+    }
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.add_checklist_item(text.trim().to_string());
+    print!("\nc+ ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn toggle_checklist_item(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path((id, index)): Path<(usize, usize)>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    let Some(done) = reminder.toggle_checklist_item(index) else {
+        return Err(checklist_item_not_found(id, index));
+    };
+    print!("\nc{} ({}) ", if done { "x" } else { "o" }, reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+pub async fn remove_checklist_item(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Path((id, index)): Path<(usize, usize)>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let recording = Recording::start(&db_file.reminders, &[id]);
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    if reminder.remove_checklist_item(index).is_none() {
+        return Err(checklist_item_not_found(id, index));
+    }
+    print!("\nc- ({}) ", reminder.name());
+    history.record(recording, &mut db_file);
+    Ok(StatusCode::OK)
+}
+
+fn checklist_item_not_found(id: usize, index: usize) -> ApiErrorResponse {
+    ApiError::new(
+        ApiErrorCode::ChecklistItemNotFound,
+        format!(
+            "There is no checklist item number {}",
+            index.saturating_add(1)
+        ),
+    )
+    .with_reminder_id(id)
+    .into()
+}
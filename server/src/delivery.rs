@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use reminder::{api_error::ApiError, delivery::RegisteredClient};
+
+use crate::{
+    auth::CurrentUser,
+    error::{json_body, lock, reminder_by_id, ApiResult},
+    DBFile,
+};
+
+type ApiState = State<Arc<Mutex<DBFile>>>;
+
+/// Registers a client under the calling user, or marks a registered one as seen.
+pub async fn register_client(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(client_id): Path<String>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    if let Some(client) = registered_client(&mut db_file.clients, &client_id, &current_user) {
+        client.seen();
+    } else {
+        db_file.clients.push(RegisteredClient::new(
+            client_id.clone(),
+            current_user.name(),
+        ));
+    }
+    print!("\nrc ({client_id}) ");
+    Ok(StatusCode::OK)
+}
+
+pub async fn all_clients(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Json<Vec<RegisteredClient>>> {
+    let db_file = lock(&db_file)?;
+    Ok(Json(
+        db_file
+            .clients
+            .iter()
+            .filter(|client| client.owner() == current_user.name().as_deref())
+            .cloned()
+            .collect(),
+    ))
+}
+
+/// The client in the body alerted the user about the reminder.
+/// Every other client keeps alerting, until someone acknowledges the reminder with `/confirm`.
+pub async fn mark_reminder_delivered(
+    State(db_file): ApiState,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<usize>,
+    client_id: Result<Json<String>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let client_id = json_body(client_id)?;
+    let Some(client) = registered_client(&mut db_file.clients, &client_id, &current_user) else {
+        return Err(ApiError::invalid_input(format!(
+            "Unknown client '{client_id}', register it with PUT /clients/{client_id} first"
+        ))
+        .into());
+    };
+    client.seen();
+    let reminder = reminder_by_id(&mut db_file.reminders, id)?;
+    reminder.mark_delivered(&client_id);
+    print!("\ndl ({}, {client_id}) ", reminder.name());
+    Ok(StatusCode::OK)
+}
+
+fn registered_client<'a>(
+    clients: &'a mut [RegisteredClient],
+    client_id: &str,
+    current_user: &CurrentUser,
+) -> Option<&'a mut RegisteredClient> {
+    clients
+        .iter_mut()
+        .find(|client| client.id() == client_id && client.owner() == current_user.name().as_deref())
+}
@@ -0,0 +1,70 @@
+use std::sync::{Mutex, MutexGuard};
+
+use axum::{
+    extract::rejection::JsonRejection,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use reminder::{
+    api_error::{ApiError, ApiErrorCode},
+    get_reminder_by_id,
+    reminder::Reminder,
+};
+
+/// An `ApiError` on its way to the client, answered with a fitting status code.
+#[allow(clippy::module_name_repetitions)]
+pub struct ApiErrorResponse(ApiError);
+
+pub type ApiResult<T = StatusCode> = Result<T, ApiErrorResponse>;
+
+impl From<ApiError> for ApiErrorResponse {
+    fn from(error: ApiError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiErrorResponse {
+    fn into_response(self) -> Response {
+        let status_code = match self.0.code() {
+            ApiErrorCode::ReminderNotFound
+            | ApiErrorCode::TemplateNotFound
+            | ApiErrorCode::ChecklistItemNotFound => StatusCode::NOT_FOUND,
+            ApiErrorCode::InvalidInput => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiErrorCode::NothingToUndo | ApiErrorCode::NothingToRedo => StatusCode::CONFLICT,
+            ApiErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiErrorCode::ServerUnreachable | ApiErrorCode::Internal => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        print!("\n! {} ", self.0);
+        (status_code, Json(self.0)).into_response()
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn lock<T>(mutex: &Mutex<T>) -> ApiResult<MutexGuard<'_, T>> {
+    mutex
+        .lock()
+        .map_err(|_| ApiError::internal("The server state is poisoned").into())
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn json_body<T>(body: Result<Json<T>, JsonRejection>) -> ApiResult<T> {
+    body.map(|Json(body)| body)
+        .map_err(|rejection| ApiError::invalid_input(rejection.body_text()).into())
+}
+
+/// Like `json_body`, but a request without a json body is `None` instead of an error.
+#[allow(clippy::missing_errors_doc)]
+pub fn optional_json_body<T>(body: Result<Json<T>, JsonRejection>) -> ApiResult<Option<T>> {
+    match body {
+        Err(JsonRejection::MissingJsonContentType(_)) => Ok(None),
+        body => json_body(body).map(Some),
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn reminder_by_id(reminders: &mut [Reminder], id: usize) -> ApiResult<&mut Reminder> {
+    get_reminder_by_id(reminders, id).ok_or_else(|| ApiError::reminder_not_found(id).into())
+}
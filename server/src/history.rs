@@ -0,0 +1,65 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::Method,
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use reminder::history::Recording;
+
+use crate::{auth::CurrentUser, DBFile};
+
+/// How the undoable operation of a request is called and whose it is, set by the history layer.
+#[derive(Clone)]
+pub struct HistoryEntry {
+    action: String,
+    owner: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Pushes what the request changed since `recording` started as one undoable operation.
+    /// Has to be called under the same lock as the change.
+    pub fn record(&self, recording: Recording, db_file: &mut DBFile) {
+        if let Some(operation) =
+            recording.finish(self.action.as_str(), self.owner.clone(), &db_file.reminders)
+        {
+            db_file.operations.push(operation);
+        }
+    }
+}
+
+/// Hands every request a `HistoryEntry`, the handlers record their changes with it.
+pub async fn attach_history_entry(
+    Extension(current_user): Extension<CurrentUser>,
+    matched_path: Option<MatchedPath>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let action = action_name(
+        req.method(),
+        matched_path.as_ref().map_or("", MatchedPath::as_str),
+    );
+    req.extensions_mut().insert(HistoryEntry {
+        action,
+        owner: current_user.name(),
+    });
+    next.run(req).await
+}
+
+/// Names an operation after the last static segment of its route, e.g. `rename` for `/reminders/:id/rename`
+/// or `delete checklist` for `DELETE /reminders/:id/checklist/:index`.
+fn action_name(method: &Method, path: &str) -> String {
+    let mut segments = path.rsplit('/');
+    match segments.next() {
+        Some(segment) if !segment.starts_with(':') && segment != "reminders" => {
+            segment.replace('_', " ")
+        }
+        _ if method == Method::DELETE => match segments.find(|segment| !segment.starts_with(':')) {
+            Some(segment) if segment != "reminders" => {
+                format!("delete {}", segment.replace('_', " "))
+            }
+            _ => String::from("delete"),
+        },
+        _ => String::from("create"),
+    }
+}
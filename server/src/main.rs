@@ -1,6 +1,4 @@
-use std::cmp::Ordering;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
 use std::thread;
 use std::{
     fs::File,
@@ -16,7 +14,7 @@ use axum::{
     middleware::Next,
     response::IntoResponse,
     routing::{delete, get, patch, post, put},
-    Router,
+    Extension, Router,
 };
 use config::Config;
 use json_store_rs::JsonStore;
@@ -24,20 +22,60 @@ use serde::{Deserialize, Serialize};
 use tower_http::cors::{Any, CorsLayer};
 
 mod api;
+mod archive;
+mod auth;
+mod batch;
+mod chain;
+mod checklist;
+mod delivery;
+mod error;
+mod history;
+mod notifier;
+mod pomodoro;
+mod stream;
+mod tick;
 use crate::api::{
-    alter_reminder_description, confirm_reminder_finish_event, cut_reminder_duration,
-    delete_reminder, force_restart_reminder, get_past_event, pause_reminder, pop_reminder_history,
-    pop_reminder_redo_history, push_reminder_duration, rename_reminder, reset_reminder_flags,
-    restart_reminder, retime_reminder, snooze_reminder, toggle_reminder_repeat,
+    all_templates, alter_reminder_description, confirm_reminder_finish_event,
+    cut_reminder_duration, delete_reminder, delete_template, force_restart_reminder, get_events,
+    get_past_event, get_reminder_history, get_stats, instantiate_template, parse_time,
+    pause_reminder, pop_reminder_history, pop_reminder_redo_history, push_reminder_duration,
+    rename_reminder, reset_reminder_flags, restart_reminder, retime_reminder,
+    save_reminder_as_template, set_reminder_priority, set_reminder_recurrence,
+    set_reminder_snooze_duration, set_reminder_tags, set_reminder_warnings, snooze_reminder,
+    toggle_reminder_nag, toggle_reminder_repeat, toggle_reminder_send_e_message,
 };
-use reminder::{past_event::PastEvent, reminder::Reminder, root_path, REMINDER_DB_FILE};
+use archive::{all_archived, delete_archived, restore_archived, Archive};
+use auth::{authenticate, authorize_reminder_access, claim_ownerless};
+use batch::{
+    batch_confirm, batch_cut_duration, batch_delete, batch_pause, batch_push_duration, batch_snooze,
+};
+use chain::{add_chain, link_reminder, start_chain};
+use checklist::{add_checklist_item, remove_checklist_item, toggle_checklist_item};
+use delivery::{all_clients, mark_reminder_delivered, register_client};
+use history::attach_history_entry;
+use pomodoro::start_pomodoro;
+use reminder::{
+    completion::CompletionLog, delivery::RegisteredClient, history::History,
+    library::ReminderTemplate, past_event::EventLog, reminder::Reminder, root_path,
+    timezone::init_timezone, REMINDER_DB_FILE, REMINDER_LIBRARY_FILE,
+};
+use stream::{spawn_broadcaster, stream_events};
+use tick::tick;
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 struct DBFile {
     reminders: Vec<Reminder>,
-    history: Vec<Vec<Reminder>>,
-    redoable_history: Vec<Vec<Reminder>>,
-    reset_history_on_change: bool,
+    #[serde(default)]
+    operations: History,
+    #[serde(default)]
+    events: EventLog,
+    #[serde(default)]
+    completions: CompletionLog,
+    #[serde(default)]
+    clients: Vec<RegisteredClient>,
+    /// The id the newest reminder got, see `next_reminder_id`.
+    #[serde(default)]
+    last_reminder_id: usize,
 }
 
 impl JsonStore for DBFile {
@@ -48,12 +86,28 @@ impl JsonStore for DBFile {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ReminderLibrary {
+    templates: Vec<ReminderTemplate>,
+}
+
+impl JsonStore for ReminderLibrary {
+    fn db_file_path() -> PathBuf {
+        let mut root_path = root_path().unwrap_or_default();
+        root_path.push(REMINDER_LIBRARY_FILE);
+        root_path
+    }
+}
+
 #[tokio::main]
 #[allow(clippy::too_many_lines)]
 async fn main() {
     println!("starting...");
     println!("version: {}", env!("CARGO_PKG_VERSION"));
     let config = Config::new();
+    if let Err(error) = init_timezone(config.timezone()) {
+        println!("{error}");
+    }
     let db_file: Arc<Mutex<DBFile>> = if let Ok(reminders) = DBFile::load() {
         Arc::new(Mutex::new(reminders))
     } else {
@@ -69,72 +123,51 @@ async fn main() {
         }
         Arc::new(Mutex::new(DBFile {
             reminders: vec![],
-            history: vec![],
-            redoable_history: vec![],
-            reset_history_on_change: false,
+            operations: History::default(),
+            events: EventLog::default(),
+            completions: CompletionLog::default(),
+            clients: vec![],
+            last_reminder_id: 0,
         }))
     };
 
-    let past_event = Arc::new(Mutex::new(PastEvent::None));
+    let mut library = ReminderLibrary::load().unwrap_or_default();
+    let mut archive = Archive::load().unwrap_or_default();
+    if let (Some(default_user), Ok(mut db_file)) = (config.auth().default_user(), db_file.lock()) {
+        let claimed = claim_ownerless(default_user, &mut db_file, &mut library, &mut archive);
+        if claimed > 0 {
+            println!(
+                "{claimed} reminders and templates without an owner now belong to {default_user}"
+            );
+            write_reminder_db(&mut db_file);
+            let _trash_bin = library.write();
+            let _trash_bin = archive.write();
+        }
+    }
+    let library = Arc::new(Mutex::new(library));
+    let archive = Arc::new(Mutex::new(archive));
 
     let db_file_clone = Arc::clone(&db_file);
-    let past_event_clone = Arc::clone(&past_event);
+    let archive_clone = Arc::clone(&archive);
+    let notification_channels = config.notifications().channels().to_vec();
+    let retention = config.retention().clone();
+    let nag = config.nag().clone();
     thread::spawn(move || loop {
-        let Ok(mut db_file) = db_file_clone.lock() else {
+        let Ok(mut db_file_guard) = db_file_clone.lock() else {
             continue;
         };
-        db_file.reminders.sort_by(|a, b| {
-            if a.finish_time().cmp(&b.finish_time()) == Ordering::Less {
-                Ordering::Greater
-            } else {
-                Ordering::Less
-            }
-        });
-        let mut writable = false;
-        for reminder in &mut db_file.reminders {
-            if reminder.remaining_duration().is_none() {
-                if let Ok(mut past_event) = past_event_clone.lock() {
-                    if !reminder.needs_confirmation()
-                        && !reminder.repeating()
-                        && !reminder.already_confirmed()
-                    {
-                        // TODO: make this configurable
-                        // TODO: repair, because no whatsapp is send..
-                        let _ = Command::new("curl")
-                            .args([
-                                "https://api.twilio.com/2010-04-01/Accounts/ACc4a89978184cd77f60c13a8515013754/Messages.json",
-                                "-X",
-                                "POST",
-                                "--data-urlencode",
-                                "To=whatsapp:+491733113571",
-                                "--data-urlencode",
-                                "From=whatsapp:+14155238886",
-                                "--data-urlencode",
-                                format!("Body=Reminder due:\n{}\n{}", reminder.name(), reminder.description()).as_str(),
-                                "-u",
-                                "ACc4a89978184cd77f60c13a8515013754:4411046064ba9ef33911da7b225def5f"
-                            ])
-                            .stdout(Stdio::null())
-                            .spawn();
-                    }
-                    reminder.request_confirmation(&mut past_event);
-                    if reminder.repeating() {
-                        reminder.restart();
-                        writable = true;
-                    }
-                }
-            }
-        }
-        for reminder in &mut db_file.reminders {
-            reminder.push_back_end_time_if_paused(time::Duration::SECOND);
-            if reminder.paused() {
-                writable = true;
-            }
-        }
+        let db_file = &mut *db_file_guard;
+        let writable = tick(
+            db_file,
+            &archive_clone,
+            &notification_channels,
+            &retention,
+            &nag,
+        );
         if writable {
-            write_reminder_db(&mut db_file);
+            write_reminder_db(db_file);
         }
-        drop(db_file);
+        drop(db_file_guard);
         std::thread::sleep(std::time::Duration::from_secs(1));
     });
 
@@ -143,8 +176,15 @@ async fn main() {
         .allow_origin(Any)
         .allow_headers(Any);
 
+    let stream_sender = spawn_broadcaster(Arc::clone(&db_file));
+
+    let library_state = (Arc::clone(&library), Arc::clone(&db_file));
+    let archive_state = (archive, Arc::clone(&db_file));
+
     let app = Router::new()
         .route("/reminders", post(add_reminder))
+        .route("/reminders/chain", post(add_chain))
+        .route("/pomodoro", post(start_pomodoro))
         .route("/reminders/reset_flags", put(reset_reminder_flags))
         .route("/reminders/:id/restart", put(restart_reminder))
         .route("/reminders/:id/force_restart", put(force_restart_reminder))
@@ -154,6 +194,30 @@ async fn main() {
         .route("/reminders/:id", delete(delete_reminder))
         .route("/reminders/:id/pause", put(pause_reminder))
         .route("/reminders/:id/toggle_repeat", put(toggle_reminder_repeat))
+        .route("/reminders/:id/toggle_nag", put(toggle_reminder_nag))
+        .route("/reminders/:id/recurrence", patch(set_reminder_recurrence))
+        .route(
+            "/reminders/:id/snooze_duration",
+            patch(set_reminder_snooze_duration),
+        )
+        .route("/reminders/:id/warnings", patch(set_reminder_warnings))
+        .route("/reminders/:id/tags", patch(set_reminder_tags))
+        .route("/reminders/:id/priority", patch(set_reminder_priority))
+        .route("/reminders/:id/next", put(link_reminder))
+        .route("/reminders/:id/start_chain", put(start_chain))
+        .route("/reminders/:id/checklist", post(add_checklist_item))
+        .route(
+            "/reminders/:id/checklist/:index/toggle_done",
+            put(toggle_checklist_item),
+        )
+        .route(
+            "/reminders/:id/checklist/:index",
+            delete(remove_checklist_item),
+        )
+        .route(
+            "/reminders/:id/toggle_send_e_message",
+            put(toggle_reminder_send_e_message),
+        )
         .route(
             "/reminders/:id/push_duration",
             patch(push_reminder_duration),
@@ -164,21 +228,72 @@ async fn main() {
             patch(alter_reminder_description),
         )
         .route("/reminders/:id/confirm", put(confirm_reminder_finish_event))
-        .layer(axum::middleware::from_fn_with_state(
-            Arc::clone(&db_file),
-            populate_reminder_history,
-        ))
+        .route("/reminders/batch/delete", put(batch_delete))
+        .route("/reminders/batch/pause", put(batch_pause))
+        .route("/reminders/batch/snooze", put(batch_snooze))
+        .route("/reminders/batch/push_duration", put(batch_push_duration))
+        .route("/reminders/batch/cut_duration", put(batch_cut_duration))
+        .route("/reminders/batch/confirm", put(batch_confirm))
+        .merge(
+            Router::new()
+                .route("/library/:index/instantiate", post(instantiate_template))
+                .with_state(library_state.clone()),
+        )
+        .layer(axum::middleware::from_fn(attach_history_entry))
         .route("/reminders/undo", put(pop_reminder_history))
         .route("/reminders/redo", put(pop_reminder_redo_history))
+        .route("/reminders/:id/delivered", put(mark_reminder_delivered))
+        .route("/clients/:client_id", put(register_client))
+        .merge(
+            Router::new()
+                .route("/archive/:index/restore", put(restore_archived))
+                .with_state(archive_state.clone()),
+        )
         .layer(axum::middleware::from_fn_with_state(
             Arc::clone(&db_file),
             write_reminder_db_middleware,
         ))
+        .route("/reminders/history", get(get_reminder_history))
         .route("/past_event", get(get_past_event))
+        .route("/events", get(get_events))
+        .route("/time/parse", post(parse_time))
+        .route("/stats", get(get_stats))
+        .route("/clients", get(all_clients))
         .route("/reminders", get(all_reminder))
         .route("/reminders/formatted", get(all_reminder_formatted))
+        .merge(
+            Router::new()
+                .route("/library", get(all_templates))
+                .route("/library/:index", delete(delete_template))
+                .route(
+                    "/reminders/:id/save_as_template",
+                    post(save_reminder_as_template),
+                )
+                .with_state(library_state),
+        )
+        .merge(
+            Router::new()
+                .route("/archive", get(all_archived))
+                .route("/archive/:index", delete(delete_archived))
+                .with_state(archive_state),
+        )
+        .merge(
+            Router::new()
+                .route("/stream", get(stream_events))
+                .with_state((stream_sender, Arc::clone(&db_file))),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&db_file),
+            authorize_reminder_access,
+        ))
+        .layer(Extension(config.snooze().clone()))
+        .layer(Extension(config.pomodoro().clone()))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(config.auth().users().to_vec()),
+            authenticate,
+        ))
         .layer(corslayer)
-        .with_state((db_file, past_event));
+        .with_state(db_file);
 
     #[allow(clippy::panic)]
     let Ok(listener) = tokio::net::TcpListener::bind(&SocketAddr::new(
@@ -198,28 +313,6 @@ async fn main() {
     }
 }
 
-#[allow(clippy::missing_errors_doc)]
-async fn populate_reminder_history(
-    State(db_file): State<Arc<Mutex<DBFile>>>,
-    req: Request<axum::body::Body>,
-    next: Next,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    if let Ok(mut db_file) = db_file.lock() {
-        if db_file.reset_history_on_change {
-            db_file.history = vec![];
-            db_file.redoable_history = vec![];
-            db_file.reset_history_on_change = false;
-        }
-        let reminders = db_file.reminders.clone();
-        db_file.history.push(reminders);
-        if db_file.history.len() > 50 {
-            db_file.history.remove(0);
-        }
-    };
-    let result = next.run(req).await;
-    Ok(result)
-}
-
 #[allow(clippy::missing_errors_doc)]
 async fn write_reminder_db_middleware(
     State(reminders): State<Arc<Mutex<DBFile>>>,
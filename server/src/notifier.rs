@@ -0,0 +1,473 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+};
+
+use config::NotificationChannel;
+use reminder::reminder::Reminder;
+
+/// Notifies every configured channel about `reminder` being due.
+/// Reminders that have `send_e_message` disabled are skipped.
+pub fn notify_all(channels: &[NotificationChannel], reminder: &Reminder) {
+    if !reminder.send_e_message() {
+        return;
+    }
+    send_in_background(channels, reminder, "notification");
+}
+
+/// Notifies every configured channel about a nagging `reminder`, that nobody confirmed yet.
+/// Unlike `notify_all` this ignores `send_e_message`, escalating is configured for all reminders.
+pub fn escalate(channels: &[NotificationChannel], reminder: &Reminder) {
+    send_in_background(channels, reminder, "escalation");
+}
+
+/// Sends every notification on its own thread, so a slow backend never blocks the tick loop.
+/// The thread waits for the backend process, so it gets reaped, and logs if it failed.
+fn send_in_background(channels: &[NotificationChannel], reminder: &Reminder, kind: &'static str) {
+    for channel in channels {
+        let notification = notification(channel, reminder);
+        let name = reminder.name().trim_end().to_string();
+        thread::spawn(move || {
+            if let Err(error) = notification.and_then(send) {
+                print!("\n{kind} failed ({name}): {error} ");
+            }
+        });
+    }
+}
+
+/// A backend process and what it gets on stdin.
+struct Notification {
+    command: Command,
+    stdin: Option<String>,
+}
+
+impl From<Command> for Notification {
+    fn from(command: Command) -> Self {
+        Self {
+            command,
+            stdin: None,
+        }
+    }
+}
+
+fn notification(
+    channel: &NotificationChannel,
+    reminder: &Reminder,
+) -> std::io::Result<Notification> {
+    match channel {
+        NotificationChannel::Webhook { url } => webhook(url, reminder),
+        NotificationChannel::Email {
+            smtp_url,
+            username,
+            password,
+            from,
+            to,
+        } => Ok(email(
+            smtp_url,
+            username.as_deref().zip(password.as_deref()),
+            from,
+            to,
+            reminder,
+        )),
+        NotificationChannel::Ntfy { url, token } => Ok(ntfy(url, token.as_deref(), reminder)),
+        NotificationChannel::Gotify { url, token } => Ok(gotify(url, token, reminder)),
+        NotificationChannel::Twilio {
+            account_sid,
+            auth_token,
+            from,
+            to,
+            api_url,
+        } => Ok(twilio(
+            api_url.as_deref().unwrap_or("https://api.twilio.com"),
+            (account_sid, auth_token),
+            from,
+            to,
+            reminder,
+        )),
+        NotificationChannel::Command { command } => Ok(shell_command(command, reminder)),
+    }
+}
+
+fn title(reminder: &Reminder) -> String {
+    format!("Reminder due: {}", reminder.name())
+}
+
+fn message(reminder: &Reminder) -> String {
+    format!("{}\n{}", reminder.name(), reminder.description())
+}
+
+/// POSTs the reminder as json.
+fn webhook(url: &str, reminder: &Reminder) -> std::io::Result<Notification> {
+    let Ok(body) = serde_json::to_string(reminder) else {
+        return Err(std::io::Error::other("could not serialize reminder"));
+    };
+    let mut command = curl();
+    command.args([url, "-X", "POST", "-H", "Content-Type: application/json"]);
+    command.args(["--data-binary", "@-"]);
+    Ok(Notification {
+        command,
+        stdin: Some(body),
+    })
+}
+
+fn email(
+    smtp_url: &str,
+    credentials: Option<(&str, &str)>,
+    from: &str,
+    to: &str,
+    reminder: &Reminder,
+) -> Notification {
+    let mut command = curl();
+    command.args([
+        "--url",
+        smtp_url,
+        "--mail-from",
+        from,
+        "--mail-rcpt",
+        to,
+        "--upload-file",
+        "-",
+    ]);
+    if let Some((username, password)) = credentials {
+        command.args(["--user", format!("{username}:{password}").as_str()]);
+    }
+    Notification {
+        command,
+        stdin: Some(format!(
+            "From: {from}\r\nTo: {to}\r\nSubject: {}\r\n\r\n{}\r\n",
+            title(reminder),
+            message(reminder)
+        )),
+    }
+}
+
+fn ntfy(url: &str, token: Option<&str>, reminder: &Reminder) -> Notification {
+    let mut command = curl();
+    command.args([url, "-H", format!("Title: {}", title(reminder)).as_str()]);
+    if let Some(token) = token {
+        command.args(["-H", format!("Authorization: Bearer {token}").as_str()]);
+    }
+    // Through stdin, so a description starting with `@` is sent as it is instead of naming a file for curl to upload
+    command.args(["--data-binary", "@-"]);
+    Notification {
+        command,
+        stdin: Some(reminder.description().to_string()),
+    }
+}
+
+fn gotify(url: &str, token: &str, reminder: &Reminder) -> Notification {
+    let mut command = curl();
+    command.args([
+        format!("{}/message?token={token}", url.trim_end_matches('/')).as_str(),
+        // `--form-string` never reads a file, even if the value starts with `@` or `<`
+        "--form-string",
+        format!("title={}", title(reminder)).as_str(),
+        "--form-string",
+        format!("message={}", reminder.description()).as_str(),
+    ]);
+    command.into()
+}
+
+fn twilio(
+    api_url: &str,
+    (account_sid, auth_token): (&str, &str),
+    from: &str,
+    to: &str,
+    reminder: &Reminder,
+) -> Notification {
+    let mut command = curl();
+    command.args([
+        format!(
+            "{}/2010-04-01/Accounts/{account_sid}/Messages.json",
+            api_url.trim_end_matches('/')
+        )
+        .as_str(),
+        "-X",
+        "POST",
+        "--data-urlencode",
+        format!("To=whatsapp:{to}").as_str(),
+        "--data-urlencode",
+        format!("From=whatsapp:{from}").as_str(),
+        "--data-urlencode",
+        format!("Body=Reminder due:\n{}", message(reminder)).as_str(),
+        "-u",
+        format!("{account_sid}:{auth_token}").as_str(),
+    ]);
+    command.into()
+}
+
+fn shell_command(command: &str, reminder: &Reminder) -> Notification {
+    let mut shell = Command::new("sh");
+    shell
+        .args(["-c", command])
+        .env("REMINDY_ID", reminder.id().to_string())
+        .env("REMINDY_NAME", reminder.name())
+        .env("REMINDY_DESCRIPTION", reminder.description())
+        .stdout(Stdio::null());
+    shell.into()
+}
+
+fn curl() -> Command {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail"])
+        .stdout(Stdio::null());
+    command
+}
+
+/// Runs the backend to completion, a non zero exit status is an error carrying its stderr.
+fn send(mut notification: Notification) -> std::io::Result<()> {
+    let mut child = notification
+        .command
+        .stdin(if notification.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let written = match (child.stdin.take(), notification.stdin) {
+        // Dropping stdin closes it, so the backend sees the end of its input.
+        (Some(mut stdin), Some(input)) => stdin.write_all(input.as_bytes()),
+        _ => Ok(()),
+    };
+    let output = child.wait_with_output()?;
+    written?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{} {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use reminder::{
+        priority::Priority,
+        reminder::{ApiReminder, Reminder, ReminderType},
+    };
+    use time::{Duration, OffsetDateTime};
+
+    use super::{email, gotify, ntfy, send, shell_command, twilio, webhook};
+
+    type StandIn = (String, JoinHandle<std::io::Result<String>>);
+
+    fn reminder() -> std::io::Result<Reminder> {
+        described("two bags")
+    }
+
+    fn described(description: &str) -> std::io::Result<Reminder> {
+        Reminder::from_api_reminder(
+            1,
+            ApiReminder {
+                name: String::from("tea"),
+                description: description.to_string(),
+                finish_time: OffsetDateTime::now_utc().saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Duration,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )
+        .map_err(|_| std::io::Error::other("invalid test reminder"))
+    }
+
+    /// Answers a single http request with `status` and hands back the request it got.
+    fn http_stand_in(status: &'static str) -> std::io::Result<StandIn> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept()?;
+            let mut reader = BufReader::new(stream.try_clone()?);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+                    break;
+                }
+                let header = line.to_lowercase();
+                if let Some(length) = header.strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap_or_default();
+                }
+                if header.starts_with("expect: 100-continue") {
+                    stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+                }
+                request.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            request.push_str(&String::from_utf8_lossy(&body));
+            stream.write_all(
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .as_bytes(),
+            )?;
+            Ok(request)
+        });
+        Ok((url, handle))
+    }
+
+    /// Accepts a single mail and hands back everything the client sent.
+    fn smtp_stand_in() -> std::io::Result<StandIn> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("smtp://{}", listener.local_addr()?);
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept()?;
+            let mut reader = BufReader::new(stream.try_clone()?);
+            stream.write_all(b"220 stand-in\r\n")?;
+            let mut conversation = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    break;
+                }
+                conversation.push_str(&line);
+                let command = line.to_uppercase();
+                let answer: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if command.starts_with("QUIT") {
+                    stream.write_all(b"221 bye\r\n")?;
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                stream.write_all(answer)?;
+            }
+            Ok(conversation)
+        });
+        Ok((url, handle))
+    }
+
+    fn received(handle: JoinHandle<std::io::Result<String>>) -> std::io::Result<String> {
+        handle
+            .join()
+            .map_err(|_| std::io::Error::other("the stand-in server crashed"))?
+    }
+
+    #[test]
+    fn webhook_posts_the_reminder_as_json() -> std::io::Result<()> {
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(webhook(&url, &reminder()?)?)?;
+        let request = received(handle)?;
+        assert!(request.starts_with("POST / HTTP/1.1"));
+        assert!(request.contains("Content-Type: application/json"));
+        assert!(request.contains("\"name\":\"tea\""));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_requests_are_errors() -> std::io::Result<()> {
+        let (url, handle) = http_stand_in("500 Internal Server Error")?;
+        assert!(send(webhook(&url, &reminder()?)?).is_err());
+        received(handle)?;
+        Ok(())
+    }
+
+    #[test]
+    fn email_is_sent_through_smtp() -> std::io::Result<()> {
+        let (url, handle) = smtp_stand_in()?;
+        send(email(
+            &url,
+            None,
+            "remindy@example.com",
+            "me@example.com",
+            &reminder()?,
+        ))?;
+        let conversation = received(handle)?;
+        assert!(conversation.contains("MAIL FROM:<remindy@example.com>"));
+        assert!(conversation.contains("RCPT TO:<me@example.com>"));
+        assert!(conversation.contains("Subject: Reminder due: tea"));
+        assert!(conversation.contains("two bags"));
+        Ok(())
+    }
+
+    #[test]
+    fn push_backends_send_title_and_description() -> std::io::Result<()> {
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(ntfy(&url, Some("secret"), &reminder()?))?;
+        let request = received(handle)?;
+        assert!(request.contains("Title: Reminder due: tea"));
+        assert!(request.contains("Authorization: Bearer secret"));
+        assert!(request.ends_with("two bags"));
+
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(gotify(&url, "app-token", &reminder()?))?;
+        let request = received(handle)?;
+        assert!(request.starts_with("POST /message?token=app-token HTTP/1.1"));
+        assert!(request.contains("Reminder due: tea"));
+        Ok(())
+    }
+
+    #[test]
+    fn descriptions_never_name_files_to_upload() -> std::io::Result<()> {
+        let secret = std::env::temp_dir().join(format!("remindy-secret-{}", std::process::id()));
+        std::fs::write(&secret, "do not upload")?;
+        let description = format!("@{}", secret.display());
+
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(ntfy(&url, None, &described(&description)?))?;
+        let ntfy_request = received(handle)?;
+
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(gotify(&url, "app-token", &described(&description)?))?;
+        let gotify_request = received(handle)?;
+
+        let (url, handle) = http_stand_in("200 OK")?;
+        send(gotify(
+            &url,
+            "app-token",
+            &described(&format!("<{}", secret.display()))?,
+        ))?;
+        let gotify_file_content_request = received(handle)?;
+
+        std::fs::remove_file(&secret)?;
+        assert!(ntfy_request.ends_with(&description));
+        assert!(gotify_request.contains(&description));
+        for request in [ntfy_request, gotify_request, gotify_file_content_request] {
+            assert!(!request.contains("do not upload"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn twilio_uses_the_configured_api_url() -> std::io::Result<()> {
+        let (url, handle) = http_stand_in("201 Created")?;
+        send(twilio(
+            &url,
+            ("AC1", "auth"),
+            "+1555",
+            "+49555",
+            &reminder()?,
+        ))?;
+        let request = received(handle)?;
+        assert!(request.starts_with("POST /2010-04-01/Accounts/AC1/Messages.json HTTP/1.1"));
+        assert!(request.contains("To=whatsapp%3A%2B49555"));
+        Ok(())
+    }
+
+    #[test]
+    fn commands_see_the_reminder_and_report_failures() -> std::io::Result<()> {
+        assert!(send(shell_command("test \"$REMINDY_NAME\" = tea", &reminder()?)).is_ok());
+        assert!(send(shell_command("exit 3", &reminder()?)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use config::Pomodoro;
+use reminder::{
+    history::Recording,
+    past_event::PastEvent,
+    pomodoro::PomodoroSession,
+    priority::Priority,
+    reminder::{my_local_offset, ApiReminder, Reminder, ReminderType},
+};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    api::next_reminder_id,
+    auth::CurrentUser,
+    error::{lock, optional_json_body, ApiResult},
+    history::HistoryEntry,
+    DBFile,
+};
+
+type ApiState = State<Arc<Mutex<DBFile>>>;
+
+/// Starts a pomodoro session with the configured phase lengths, named by the optional body.
+pub async fn start_pomodoro(
+    State(db_file): ApiState,
+    Extension(history): Extension<HistoryEntry>,
+    Extension(pomodoro): Extension<Pomodoro>,
+    Extension(current_user): Extension<CurrentUser>,
+    name: Result<Json<String>, JsonRejection>,
+) -> ApiResult {
+    let mut db_file = lock(&db_file)?;
+    let db_file = &mut *db_file;
+    let name = optional_json_body(name)?
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "pomodoro".to_string());
+    let session = PomodoroSession::new(
+        Duration::minutes(pomodoro.work_minutes().into()),
+        Duration::minutes(pomodoro.short_break_minutes().into()),
+        Duration::minutes(pomodoro.long_break_minutes().into()),
+        pomodoro.cycles(),
+    );
+    let now = OffsetDateTime::now_utc().to_offset(my_local_offset());
+    let new_id = next_reminder_id(db_file);
+    let recording = Recording::start(&db_file.reminders, &[new_id]);
+    let mut new_reminder = Reminder::from_api_reminder(
+        new_id,
+        ApiReminder {
+            name: name.trim().to_string(),
+            description: String::new(),
+            #[allow(clippy::arithmetic_side_effects)]
+            finish_time: now + session.phase_duration(),
+            reminder_type: ReminderType::Duration,
+            tags: vec![],
+            priority: Priority::default(),
+            checklist: vec![],
+        },
+    )?;
+    new_reminder.set_owner(current_user.name());
+    new_reminder.start_pomodoro(session);
+    db_file
+        .events
+        .push(PastEvent::ReminderCreated(new_reminder.clone()));
+    print!("\nnp ({}) ", new_reminder.name());
+    db_file.reminders.push(new_reminder);
+    history.record(recording, db_file);
+    Ok(StatusCode::OK)
+}
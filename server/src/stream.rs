@@ -0,0 +1,145 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use reminder::stream_event::StreamEvent;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+use crate::{
+    auth::CurrentUser,
+    error::{lock, ApiResult},
+    DBFile,
+};
+
+type StreamState = State<(broadcast::Sender<StreamEvent>, Arc<Mutex<DBFile>>)>;
+
+/// Watches the reminders and the event log and broadcasts every change to all `/stream` connections.
+/// Runs on its own thread like the reminder loop, so waiting for the lock never blocks the async runtime.
+pub fn spawn_broadcaster(db_file: Arc<Mutex<DBFile>>) -> broadcast::Sender<StreamEvent> {
+    let (sender, _) = broadcast::channel(64);
+    let broadcaster = sender.clone();
+    thread::spawn(move || {
+        let mut last_reminders = vec![];
+        let mut last_event_id = 0;
+        loop {
+            if let Ok(db_file) = db_file.lock() {
+                if let Some(diff) = StreamEvent::diff(&last_reminders, &db_file.reminders) {
+                    let _ = broadcaster.send(diff);
+                    last_reminders.clone_from(&db_file.reminders);
+                }
+                for event in db_file.events.since(last_event_id) {
+                    last_event_id = event.id();
+                    let _ = broadcaster.send(StreamEvent::Event(event));
+                }
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+    });
+    sender
+}
+
+pub async fn stream_events(
+    State((sender, db_file)): StreamState,
+    Extension(current_user): Extension<CurrentUser>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    let receiver = sender.subscribe();
+    let snapshot = StreamEvent::Snapshot(lock(&db_file)?.reminders.clone());
+    let events = tokio_stream::once(snapshot)
+        .chain(BroadcastStream::new(receiver).map(move |event| {
+            match event {
+                Ok(event) => event,
+                // This connection fell behind, so it gets a fresh snapshot instead of the missed diffs
+                Err(_) => StreamEvent::Snapshot(
+                    db_file
+                        .lock()
+                        .map(|db_file| db_file.reminders.clone())
+                        .unwrap_or_default(),
+                ),
+            }
+        }))
+        .filter_map(move |event| visible_part(event, &current_user))
+        .map(|event| Event::default().json_data(event));
+    print!("\nstream ");
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Strips everything `current_user` may not see from `event`, `None` if nothing is left.
+fn visible_part(event: StreamEvent, current_user: &CurrentUser) -> Option<StreamEvent> {
+    match event {
+        StreamEvent::Snapshot(mut reminders) => {
+            reminders.retain(|reminder| current_user.can_access(reminder.owner()));
+            Some(StreamEvent::Snapshot(reminders))
+        }
+        StreamEvent::Diff {
+            mut changed,
+            removed,
+        } => {
+            changed.retain(|reminder| current_user.can_access(reminder.owner()));
+            // Removed ids of other users are unknown to the client and simply ignored there
+            if changed.is_empty() && removed.is_empty() {
+                None
+            } else {
+                Some(StreamEvent::Diff { changed, removed })
+            }
+        }
+        StreamEvent::Event(event) => current_user
+            .can_access(event.event().owner())
+            .then_some(StreamEvent::Event(event)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use reminder::{
+        api_error::ApiError,
+        priority::Priority,
+        reminder::{ApiReminder, Reminder, ReminderType},
+        stream_event::StreamEvent,
+    };
+    use time::{Duration, OffsetDateTime};
+
+    use super::spawn_broadcaster;
+    use crate::DBFile;
+
+    #[test]
+    fn changes_are_broadcast_without_an_async_runtime() -> Result<(), ApiError> {
+        let db_file = Arc::new(Mutex::new(DBFile::default()));
+        let mut receiver = spawn_broadcaster(Arc::clone(&db_file)).subscribe();
+        let reminder = Reminder::from_api_reminder(
+            1,
+            ApiReminder {
+                name: "stream".to_string(),
+                description: String::new(),
+                finish_time: OffsetDateTime::now_utc().saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Duration,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )?;
+        db_file
+            .lock()
+            .map_err(|_| ApiError::internal("poisoned"))?
+            .reminders
+            .push(reminder);
+        let Ok(StreamEvent::Diff { changed, removed }) = receiver.blocking_recv() else {
+            return Err(ApiError::internal("Expected a diff"));
+        };
+        assert_eq!(
+            changed.iter().map(Reminder::id).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert!(removed.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Mutex;
+
+use config::{Nag, NotificationChannel, Retention};
+use reminder::{get_reminder_by_id, priority::SortOrder};
+
+use crate::{
+    archive::{enforce_retention, Archive},
+    notifier::{escalate, notify_all},
+    DBFile,
+};
+
+/// One pass of the reminder loop, that runs every second:
+/// alerts due reminders, moves repeating ones on, continues chains and enforces the retention.
+/// Returns if anything changed, that needs to be written.
+pub fn tick(
+    db_file: &mut DBFile,
+    archive: &Mutex<Archive>,
+    channels: &[NotificationChannel],
+    retention: &Retention,
+    nag: &Nag,
+) -> bool {
+    SortOrder::Due.sort(&mut db_file.reminders);
+    let mut writable = false;
+    let last_event_id = db_file.events.last_id();
+    let mut chains_to_continue: Vec<usize> = vec![];
+    for reminder in &mut db_file.reminders {
+        if reminder.remaining_duration().is_none() && !reminder.is_note() {
+            if !reminder.needs_confirmation()
+                && !reminder.repeating()
+                && !reminder.already_confirmed()
+            {
+                notify_all(channels, reminder);
+            }
+            if reminder.repeating()
+                || (!reminder.needs_confirmation() && !reminder.already_confirmed())
+            {
+                db_file.completions.fired(reminder);
+                chains_to_continue.extend(reminder.next_in_chain());
+                writable = true;
+            }
+            reminder.request_confirmation(&mut db_file.events);
+            if reminder.repeating() {
+                reminder.recur();
+                writable = true;
+            }
+        }
+        reminder.request_warning(&mut db_file.events);
+        if reminder.nag_again(
+            time::Duration::minutes(i64::from(nag.minutes())),
+            &mut db_file.events,
+        ) && nag
+            .escalate_after()
+            .is_some_and(|escalate_after| reminder.nag_count() == escalate_after as usize)
+        {
+            escalate(channels, reminder);
+        }
+    }
+    for id in chains_to_continue {
+        if let Some(reminder) = get_reminder_by_id(&mut db_file.reminders, id) {
+            reminder.continue_chain(&mut db_file.events);
+            print!("\nch ({}) ", reminder.name());
+        }
+    }
+    if enforce_retention(db_file, archive, retention) {
+        writable = true;
+    }
+    if db_file.events.last_id() != last_event_id {
+        writable = true;
+    }
+    for reminder in &mut db_file.reminders {
+        reminder.push_back_end_time_if_paused(time::Duration::SECOND);
+        if reminder.paused() || reminder.waiting() {
+            writable = true;
+        }
+    }
+    writable
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use config::{Nag, Retention};
+    use reminder::{
+        api_error::ApiError,
+        priority::Priority,
+        reminder::{ApiReminder, Reminder, ReminderType},
+    };
+    use serde_json::json;
+    use time::{Duration, OffsetDateTime};
+
+    use super::tick;
+    use crate::{archive::Archive, DBFile};
+
+    /// Ticks like the reminder loop does, without any notification channels.
+    fn run_ticks(db_file: &mut DBFile, ticks: usize) -> Result<(), ApiError> {
+        let nag: Nag = serde_json::from_value(json!({ "minutes": 5 }))
+            .map_err(|error| ApiError::internal(error.to_string()))?;
+        for _ in 0..ticks {
+            tick(
+                db_file,
+                &Mutex::new(Archive::default()),
+                &[],
+                &Retention::default(),
+                &nag,
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn repeating_date_reminders_fire_once_and_come_back_next_year() -> Result<(), ApiError> {
+        let now = OffsetDateTime::now_utc();
+        let mut reminder = Reminder::from_api_reminder(
+            1,
+            ApiReminder {
+                name: "anniversary".to_string(),
+                description: String::new(),
+                finish_time: now.saturating_add(Duration::hours(1)),
+                reminder_type: ReminderType::Date,
+                tags: vec![],
+                priority: Priority::default(),
+                checklist: vec![],
+            },
+        )?;
+        assert_eq!(reminder.toggle_repeat(), Some(true));
+        let finish_time = now.saturating_sub(Duration::minutes(1));
+        reminder.set_finish_time(finish_time);
+        let mut db_file = DBFile {
+            reminders: vec![reminder],
+            ..DBFile::default()
+        };
+
+        run_ticks(&mut db_file, 3)?;
+
+        assert_eq!(db_file.events.last_id(), 1);
+        assert_eq!(db_file.completions.completions().len(), 1);
+        let reminder = db_file
+            .reminders
+            .first()
+            .ok_or_else(|| ApiError::internal("The reminder is gone"))?;
+        assert!(reminder.repeating());
+        assert!(reminder.remaining_duration().is_some());
+        assert_eq!(reminder.finish_time().year(), finish_time.year() + 1);
+        Ok(())
+    }
+}